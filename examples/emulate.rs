@@ -15,13 +15,17 @@ use simpleos::util::RingBuf;
 use simpleos::Result;
 use std::io::{stdin, Read, Write};
 use std::sync::Arc;
-use std::sync::Mutex;
+use std::sync::{Condvar, Mutex};
 use std::thread;
 use termion::raw::IntoRawMode;
 use termion::raw::RawTerminal;
 
 struct CpuEmulate;
 
+// 模拟屏蔽中断: 同一时间只有一个线程处于临界区, 同一线程可嵌套进入
+static CRITICAL_OWNER: Mutex<Option<thread::ThreadId>> = Mutex::new(None);
+static CRITICAL_RELEASED: Condvar = Condvar::new();
+
 impl Driver for CpuEmulate {
     fn driver_init(&mut self) -> Result<()> {
         Ok(())
@@ -49,6 +53,29 @@ impl CpuDriver for CpuEmulate {
             .restore_terminal();
         panic!("Panic: {}", panic_info);
     }
+    fn cpu_idle(&mut self, max_sleep_ms: Option<u32>) {
+        // 没有定时器时无法被中断唤醒, 只休眠一个节拍
+        let ms = max_sleep_ms.unwrap_or(1);
+        thread::sleep(std::time::Duration::from_millis(ms as u64));
+    }
+    fn cpu_enter_critical(&mut self) -> usize {
+        let current = thread::current().id();
+        let mut owner = CRITICAL_OWNER.lock().unwrap();
+        if *owner == Some(current) {
+            return 1; // 嵌套进入
+        }
+        while owner.is_some() {
+            owner = CRITICAL_RELEASED.wait(owner).unwrap();
+        }
+        *owner = Some(current);
+        0
+    }
+    fn cpu_exit_critical(&mut self, state: usize) {
+        if state == 0 {
+            *CRITICAL_OWNER.lock().unwrap() = None;
+            CRITICAL_RELEASED.notify_one();
+        }
+    }
 }
 
 struct SysTickEmulate;
@@ -84,6 +111,7 @@ impl SysTickDriver for SysTickEmulate {
 struct TtyEmulate {
     rx: Arc<Mutex<RingBuf<u8, 1024>>>,
     rx_break: Arc<Mutex<bool>>,
+    rx_suspend: Arc<Mutex<bool>>,
     raw_term: Arc<Mutex<Option<RawTerminal<std::io::Stdout>>>>,
 }
 
@@ -92,6 +120,7 @@ impl TtyEmulate {
         TtyEmulate {
             rx: Arc::new(Mutex::new(RingBuf::new())),
             rx_break: Arc::new(Mutex::new(false)),
+            rx_suspend: Arc::new(Mutex::new(false)),
             raw_term: Arc::new(Mutex::new(None)),
         }
     }
@@ -107,6 +136,7 @@ impl Driver for TtyEmulate {
         let raw_term_clone = self.raw_term.clone();
         let rx_clone = self.rx.clone();
         let rx_break_clone = self.rx_break.clone();
+        let rx_suspend_clone = self.rx_suspend.clone();
         thread::spawn(move || {
             let mut stdin = stdin();
             let raw = std::io::stdout().into_raw_mode().unwrap();
@@ -120,6 +150,11 @@ impl Driver for TtyEmulate {
                         *rx_break = true;
                         continue;
                     }
+                    if buffer[0] == 26 {
+                        let mut rx_suspend = rx_suspend_clone.lock().unwrap();
+                        *rx_suspend = true;
+                        continue;
+                    }
                     let mut rx = rx_clone.lock().unwrap();
                     rx.push(buffer[0]);
                 }
@@ -160,6 +195,16 @@ impl TtyDriver for TtyEmulate {
             false
         }
     }
+
+    fn tty_get_suspend(&mut self) -> bool {
+        let mut rx_suspend = self.rx_suspend.lock().unwrap();
+        if *rx_suspend {
+            *rx_suspend = false;
+            true
+        } else {
+            false
+        }
+    }
 }
 
 struct BoardEmulate {
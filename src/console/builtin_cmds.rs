@@ -1,7 +1,7 @@
 use crate::console::CmdParser;
-use crate::executor::{Executor, ExitCode};
+use crate::executor::{Executor, ExitCode, Signal};
 use crate::sys::SimpleOs;
-use crate::{println, sys};
+use crate::{print, println, sys};
 use alloc::rc::Rc;
 use alloc::string::ToString;
 use alloc::{boxed::Box, string::String, vec::Vec};
@@ -37,27 +37,122 @@ impl BuiltinCmds {
         }
     }
 
+    pub fn cmd_echo(&self, args: &[String]) -> ExitCode {
+        println!("{}", args[1..].join(" "));
+        0
+    }
+
     pub fn cmd_ps(&self, _args: &Vec<String>) -> ExitCode {
-        let task_list = Executor::task_list();
-        println!("id\ttask");
-        for (task_id, name) in task_list.iter() {
-            println!("{}\t{}", task_id, name);
+        let task_list = Executor::task_info_list();
+        println!("id\tppid\tpgid\tprio\tstate\ttask");
+        for info in task_list.iter() {
+            let state = if info.exit_code.is_some() {
+                "Z"
+            } else if info.paused {
+                "T"
+            } else {
+                "R"
+            };
+            let ppid = match info.parent {
+                Some(parent) => parent.to_string(),
+                None => "-".to_string(),
+            };
+            println!(
+                "{}\t{}\t{}\t{:?}\t{}\t{}",
+                info.id, ppid, info.pgid, info.priority, state, info.cmd
+            );
         }
         0
     }
 
+    pub async fn cmd_top(&self, args: &[String]) -> ExitCode {
+        let interval_ms = match args.get(1) {
+            Some(sec_str) => match sec_str.parse::<f32>() {
+                Ok(sec) if sec > 0.0 => (sec * 1000.0) as u32,
+                _ => {
+                    println!("Invalid interval: {}", sec_str);
+                    return 1;
+                }
+            },
+            None => 1000,
+        };
+        let mut last_list = Executor::task_info_list();
+        let mut last_ms = sys::get_system_ms64();
+        loop {
+            sys::sleep_ms(interval_ms).await;
+            // 只显示运行中的任务
+            let mut task_list = Executor::task_info_list();
+            task_list.retain(|info| info.exit_code.is_none());
+            let now_ms = sys::get_system_ms64();
+            let elapsed_us = ((now_ms - last_ms) * 1000).max(1);
+            let mut busy_us = 0u64;
+
+            // 清屏并移动光标到左上角
+            print!("\x1b[2J\x1b[H");
+            println!("id\tprio\tstate\tcpu%\tpolls\tmax_us\tidle_ms\ttask");
+            for info in task_list.iter() {
+                let stats = &info.stats;
+                // 与上次快照比较, 新任务从0开始计算
+                let (last_us, last_polls) = last_list
+                    .iter()
+                    .find(|last| last.id == info.id)
+                    .map(|last| (last.stats.total_poll_us, last.stats.poll_count))
+                    .unwrap_or((0, 0));
+                let delta_us = stats.total_poll_us.saturating_sub(last_us);
+                busy_us += delta_us;
+                let permille = delta_us * 1000 / elapsed_us;
+                let state = if info.paused { "T" } else { "R" };
+                println!(
+                    "{}\t{:?}\t{}\t{}.{}\t{}\t{}\t{}\t{}",
+                    info.id,
+                    info.priority,
+                    state,
+                    permille / 10,
+                    permille % 10,
+                    stats.poll_count.wrapping_sub(last_polls),
+                    stats.max_poll_us,
+                    now_ms.saturating_sub(stats.last_run_ms),
+                    info.cmd
+                );
+            }
+            let busy_permille = (busy_us * 1000 / elapsed_us).min(1000);
+            println!(
+                "tasks: {}, cpu: {}.{}%, uptime: {}s (Ctrl+C to quit)",
+                task_list.len(),
+                busy_permille / 10,
+                busy_permille % 10,
+                now_ms / 1000
+            );
+
+            last_list = task_list;
+            last_ms = now_ms;
+        }
+    }
+
     pub fn cmd_kill(&self, args: &Vec<String>) -> ExitCode {
-        if let Some(id_str) = args.get(1) {
+        // kill -9 立即强制终止, 否则先请求任务自行退出
+        let force = args.get(1).is_some_and(|arg| arg == "-9");
+        let id_arg = if force { args.get(2) } else { args.get(1) };
+        if let Some(id_str) = id_arg {
             if let Ok(id) = id_str.parse::<u16>() {
-                Executor::kill(id);
-                println!("Killed task with ID {}", id);
-                0
+                let sent = if force {
+                    Executor::send_signal(id, Signal::SIGKILL)
+                } else {
+                    Executor::kill(id)
+                };
+                if sent {
+                    println!("Killed task with ID {}", id);
+                    0
+                } else {
+                    println!("No such task: {}", id);
+                    1
+                }
             } else {
                 println!("Invalid task ID: {}", id_str);
                 1
             }
         } else {
-            println!("Usage: kill <task_id>");
+            println!("Usage: kill [-9] <task_id>");
             2
         }
     }
@@ -131,20 +226,42 @@ impl CmdParser for BuiltinCmds {
             ("help|?", "Show this help message"),
             ("reset", "Perform a system reset"),
             ("sleep <seconds>", "Sleep for a specified number of seconds"),
+            ("echo [args...]", "Print arguments, e.g. echo $? for last exit code"),
             ("ps", "Show running tasks"),
-            ("kill <task_id>", "Terminate a task"),
+            ("top [seconds]", "Show CPU usage per task, refresh periodically"),
+            ("kill [-9] <task_id>", "Terminate a task, -9 to force"),
             ("free", "Show free memory"),
             ("pref", "Show task polling frequency"),
             ("panic", "Trigger a panic"),
         ]
     }
 
+    fn complete(&self, args: &[String]) -> Option<Vec<String>> {
+        match args[0].as_str() {
+            // 补全运行中的任务ID
+            "kill" => {
+                let mut ids: Vec<String> = Executor::task_info_list()
+                    .iter()
+                    .filter(|info| info.exit_code.is_none())
+                    .map(|info| info.id.to_string())
+                    .collect();
+                if args.len() == 2 {
+                    ids.push("-9".to_string());
+                }
+                Some(ids)
+            }
+            _ => None,
+        }
+    }
+
     async fn parse(&self, args: &Vec<String>) -> ExitCode {
         if let Some(cmd) = args.get(0) {
             match cmd.as_str() {
                 "reset" => self.cmd_reset(&args),
                 "sleep" => self.cmd_sleep(&args).await,
+                "echo" => self.cmd_echo(args),
                 "ps" => self.cmd_ps(&args),
+                "top" => self.cmd_top(args).await,
                 "kill" => self.cmd_kill(&args),
                 "free" => self.cmd_free(&args),
                 "pref" => self.cmd_pref(&args).await,
@@ -8,4 +8,12 @@ use crate::executor::ExitCode;
 pub trait CmdParser {
     fn help(&self) -> &'static [(&'static str, &'static str)];
     async fn parse(&self, args: &Vec<String>) -> ExitCode;
+
+    /// Tab 补全命令参数, args 为光标前的参数, 最后一个为待补全的部分 (可能为空)
+    ///
+    /// 返回候选的完整参数, 由控制台按前缀过滤; 返回 None 表示不处理, 由控制台补全文件路径
+    #[allow(unused)]
+    fn complete(&self, args: &[String]) -> Option<Vec<String>> {
+        None
+    }
 }
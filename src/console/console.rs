@@ -1,14 +1,53 @@
-use crate::console::CmdParser;
-use crate::executor::{Executor, ExitCode};
-use crate::sys::SimpleOs;
+use crate::console::{
+    escape_partial, parse_cmdline, parse_partial_cmdline, CmdParser, Command, Pipeline, RedirectKind,
+};
+use crate::driver::fs::{File, Fs};
+use crate::executor::{Executor, ExitCode, Signal, TaskId};
+use crate::sys::{SimpleOs, Stdin, Stdout};
 use crate::{println, singleton, sys};
 use alloc::boxed::Box;
+use alloc::collections::btree_map::BTreeMap;
 use alloc::collections::vec_deque::VecDeque;
+use alloc::rc::Rc;
 use alloc::string::{String, ToString};
 use alloc::vec::Vec;
+use anyhow::{anyhow, Result};
+use core::cell::RefCell;
 
 const HISTORY_SIZE: usize = 10; // 历史记录最大条数
 const LINE_BUFFER_SIZE: usize = 512; // 每行最大字符数
+const TTY_POLL_INTERVAL_MS: u32 = 10; // 无输入时的终端轮询间隔, 期间执行器可进入空闲
+const STOPPED_EXIT_CODE: ExitCode = -19; // 前台任务被 Ctrl+Z 挂起时的退出码, 对应 SIGSTOP
+const SYNTAX_ERROR_EXIT_CODE: ExitCode = 2; // 命令行语法错误时的退出码
+const EOT: u8 = 4; // 重定向的标准输入结束时 getc 返回的字符, 即 Ctrl+D
+
+// 需要访问控制台状态的命令, 在控制台任务中直接执行
+const CONSOLE_CMDS: &[&str] = &["help", "?", "set", "unset", "jobs", "fg", "bg"];
+
+// 控制台内置命令的帮助
+const CONSOLE_CMDS_HELP: &[(&str, &str)] = &[
+    ("NAME=value", "Set a console variable, use as $NAME or ${NAME}"),
+    ("set", "List console variables"),
+    ("unset <name>", "Remove a console variable"),
+    ("<cmd> &", "Run command in background"),
+    ("<cmd1> && <cmd2>", "Run cmd2 if cmd1 succeeded"),
+    ("<cmd1> || <cmd2>", "Run cmd2 if cmd1 failed"),
+    ("<cmd1> | <cmd2>", "Pipe output of cmd1 to input of cmd2"),
+    ("<cmd> > <file>", "Write output to file, >> to append"),
+    ("<cmd> < <file>", "Read input from file"),
+    ("jobs", "List background and stopped jobs"),
+    ("fg [job_id]", "Continue job in foreground"),
+    ("bg [job_id]", "Continue stopped job in background"),
+];
+
+// 作业: 后台运行或被 Ctrl+Z 挂起的命令或管道
+struct Job {
+    id: u32,            // 作业号
+    pgid: TaskId,       // 任务组ID, 即第一条命令的任务ID
+    pids: Vec<TaskId>,  // 管道中各命令的任务ID
+    cmdline: String,    // 命令行
+    stopped: bool,      // 是否被挂起
+}
 
 #[derive(Debug, Clone, Copy)]
 enum EscapeState {
@@ -27,7 +66,13 @@ pub struct Console {
     cursor_pos: usize,
     // ANSI转义序列状态
     escape_state: EscapeState,
+    last_key_tab: bool, // 上一个按键是否为 Tab, 连续两次 Tab 时列出补全候选项
     cmds_parser_list: VecDeque<Box<dyn CmdParser>>,
+    // 作业控制
+    jobs: Vec<Job>,
+    // 变量
+    vars: BTreeMap<String, String>,
+    last_exit_code: ExitCode, // 上一条命令的退出码, 即 $?
 }
 
 singleton!(Console {
@@ -37,7 +82,11 @@ singleton!(Console {
     current_line: Vec::new(),
     cursor_pos: 0,
     escape_state: EscapeState::Normal,
+    last_key_tab: false,
     cmds_parser_list: VecDeque::new(),
+    jobs: Vec::new(),
+    vars: BTreeMap::new(),
+    last_exit_code: 0,
 });
 
 #[allow(unused)]
@@ -47,6 +96,79 @@ impl Console {
         console.cmds_parser_list.push_back(Box::new(cmds));
     }
 
+    /// 创建任务执行一条命令, args[0] 为命令名, 返回任务ID
+    ///
+    /// 命令作为独立的任务组, 其创建的子任务可与其一同被终止
+    pub fn spawn_cmd(args: Vec<String>) -> TaskId {
+        let name = args.first().cloned().unwrap_or_default();
+        let pid = Executor::spawn(
+            name,
+            Box::pin(async move {
+                let parser_list = &Console::get_mut().cmds_parser_list;
+                for parser in parser_list.iter() {
+                    let exit_code = parser.parse(&args).await;
+                    if exit_code != 127 {
+                        return exit_code;
+                    }
+                }
+                println!("Unknown command: {}", args.join(" "));
+                127
+            }),
+        );
+        Executor::set_pgid(pid, pid);
+        pid
+    }
+
+    /// 将命令行解析为一条命令的参数并展开变量, 供定时任务等执行命令行字符串
+    pub fn parse_args(cmdline: &str) -> Result<Vec<String>> {
+        let mut pipelines = parse_cmdline(cmdline)?;
+        match pipelines.pop() {
+            Some(pipeline)
+                if pipelines.is_empty()
+                    && !pipeline.background
+                    && pipeline.commands.len() == 1
+                    && pipeline.commands[0].redirects.is_empty() =>
+            {
+                let console = Console::get_mut();
+                Ok(console.expand_words(&pipeline.commands[0]))
+            }
+            _ => Err(anyhow!("expected a single command: {}", cmdline)),
+        }
+    }
+
+    /// 设置控制台变量
+    pub fn set_var(name: &str, value: &str) {
+        let console = Console::get_mut();
+        console.vars.insert(String::from(name), String::from(value));
+    }
+
+    /// 读取控制台变量, "?" 为上一条命令的退出码
+    pub fn get_var(name: &str) -> Option<String> {
+        let console = Console::get_mut();
+        console.lookup_var(name)
+    }
+
+    /// 删除控制台变量, 返回变量是否存在
+    pub fn unset_var(name: &str) -> bool {
+        let console = Console::get_mut();
+        console.vars.remove(name).is_some()
+    }
+
+    fn lookup_var(&self, name: &str) -> Option<String> {
+        if name == "?" {
+            return Some(self.last_exit_code.to_string());
+        }
+        self.vars.get(name).cloned()
+    }
+
+    fn expand_words(&self, command: &Command) -> Vec<String> {
+        command
+            .words
+            .iter()
+            .map(|word| word.expand(|name| self.lookup_var(name)))
+            .collect()
+    }
+
     pub fn set_prompt(prompt: &str) {
         let console = Console::get_mut();
         console.prompt = String::from(prompt);
@@ -182,13 +304,262 @@ impl Console {
         }
     }
 
-    async fn exec_cmd(&mut self, args: Vec<String>) {
+    // Tab 补全光标前的单词, 有多个候选项时补全公共前缀, list 为 true 时列出候选项
+    fn complete(&mut self, list: bool) {
+        let line = String::from_utf8_lossy(&self.current_line[..self.cursor_pos]).into_owned();
+        // 与执行时相同的引号和转义规则拆分, 单词已去掉引号和转义
+        let partial = parse_partial_cmdline(&line);
+        if partial.in_comment {
+            return;
+        }
+        let word = partial.word.as_str();
+        let mut args = partial.args.clone();
+
+        let mut candidates = if partial.after_redirect {
+            Self::complete_path(word)
+        } else if args.is_empty() {
+            self.complete_command()
+        } else {
+            args.push(word.to_string());
+            self.complete_args(&args).unwrap_or_else(|| Self::complete_path(word))
+        };
+        candidates.retain(|candidate| candidate.starts_with(word));
+        candidates.sort();
+        candidates.dedup();
+
+        match candidates.as_slice() {
+            [] => {}
+            [candidate] => {
+                // 唯一候选项, 非目录时闭合引号并追加空格
+                self.insert_str(&escape_partial(&candidate[word.len()..], partial.quote));
+                if !candidate.ends_with('/') {
+                    if let Some(quote) = partial.quote {
+                        self.insert_str(quote.encode_utf8(&mut [0; 4]));
+                    }
+                    self.insert_char(b' ');
+                }
+            }
+            [first, rest @ ..] => {
+                // 补全所有候选项的公共前缀
+                let mut common = first.as_str();
+                for candidate in rest.iter() {
+                    while !candidate.starts_with(common) {
+                        common = &common[..common.len() - common.chars().last().map_or(0, char::len_utf8)];
+                    }
+                }
+                if common.len() > word.len() {
+                    self.insert_str(&escape_partial(&common[word.len()..], partial.quote));
+                } else if list {
+                    // 路径只显示最后一级
+                    let base = word.rfind('/').map(|i| i + 1).unwrap_or(0);
+                    let names: Vec<&str> = candidates.iter().map(|c| &c[base..]).collect();
+                    SimpleOs::tty().tty_write(b"\n");
+                    SimpleOs::tty().tty_write(names.join("  ").as_bytes());
+                    SimpleOs::tty().tty_write(b"\n");
+                    self.show_prompt();
+                    self.redraw_line();
+                }
+            }
+        }
+    }
+
+    // 在光标位置插入文本
+    fn insert_str(&mut self, text: &str) {
+        for c in text.bytes() {
+            self.insert_char(c);
+        }
+    }
+
+    // 命令名候选项: 控制台内置命令和各命令解析器帮助中的命令名
+    fn complete_command(&self) -> Vec<String> {
+        let mut names: Vec<String> = CONSOLE_CMDS.iter().map(|cmd| cmd.to_string()).collect();
+        for parser in self.cmds_parser_list.iter() {
+            for (usage, _) in parser.help().iter() {
+                // 例如 "help|?", "kill [-9] <task_id>"
+                let cmd = usage.split_whitespace().next().unwrap_or("");
+                names.extend(cmd.split('|').map(|name| name.to_string()));
+            }
+        }
+        names.retain(|name| name.starts_with(|c: char| c.is_ascii_alphanumeric()));
+        names
+    }
+
+    // 命令参数候选项, 由控制台内置命令或命令解析器提供
+    fn complete_args(&self, args: &[String]) -> Option<Vec<String>> {
+        if matches!(args[0].as_str(), "fg" | "bg") {
+            return Some(self.jobs.iter().map(|job| job.id.to_string()).collect());
+        }
+        self.cmds_parser_list
+            .iter()
+            .find_map(|parser| parser.complete(args))
+    }
+
+    // 文件路径候选项, 相对路径基于当前目录, 目录以 / 结尾
+    fn complete_path(word: &str) -> Vec<String> {
+        let dir = match word.rfind('/') {
+            Some(index) => &word[..index + 1],
+            None => "",
+        };
+        let path = if dir.is_empty() {
+            Fs::get_cwd()
+        } else {
+            Fs::to_absolute_path(dir)
+        };
+        let entries = match Fs::readdir(&path) {
+            Ok(entries) => entries,
+            Err(_) => return Vec::new(),
+        };
+        entries
+            .iter()
+            .filter(|entry| entry.name() != "." && entry.name() != "..")
+            .map(|entry| {
+                let mut candidate = String::from(dir);
+                candidate.push_str(entry.name());
+                if entry.is_dir() {
+                    candidate.push('/');
+                }
+                candidate
+            })
+            .collect()
+    }
+
+    // 执行一条管道, 返回最后一条命令的退出码
+    async fn exec_pipeline(&mut self, pipeline: &Pipeline) -> ExitCode {
         SimpleOs::tty().tty_flush();
         SimpleOs::tty().tty_clear_rx();
-        if let Some(cmd) = args.get(0) {
+
+        // 只有变量赋值时设置变量
+        if let [command] = pipeline.commands.as_slice() {
+            let assignments: Option<Vec<_>> = command.words.iter().map(|word| word.as_assignment()).collect();
+            if let (Some(assignments), true) = (assignments, command.redirects.is_empty()) {
+                for (name, value) in assignments {
+                    let value = value.expand(|name| self.lookup_var(name));
+                    self.vars.insert(name, value);
+                }
+                return 0;
+            }
+        }
+
+        let mut pids = Vec::new();
+        let mut texts = Vec::new();
+        let mut last_exit_code = None; // 最后一条命令未创建任务时的退出码
+        let mut pipe_in: Option<Stdin> = None;
+        let count = pipeline.commands.len();
+        for (index, command) in pipeline.commands.iter().enumerate() {
+            let args = self.expand_words(command);
+            texts.push(self.command_text(command, &args));
+
+            // 管道连接相邻的命令, 重定向优先于管道
+            let mut stdin = pipe_in.take();
+            let mut stdout: Option<Stdout> = None;
+            if index + 1 < count {
+                let (writer, reader) = sys::pipe();
+                stdout = Some(Rc::new(RefCell::new(writer)));
+                pipe_in = Some(Rc::new(RefCell::new(reader)));
+            }
+            let exit_code = match self.open_redirects(command, &mut stdin, &mut stdout) {
+                Ok(()) if Self::is_console_cmd(&args[0]) => {
+                    // 控制台内置命令在控制台任务中执行, 临时替换控制台的标准输入输出
+                    let console_id = Executor::current_task_id().unwrap_or_default();
+                    let (old_stdin, old_stdout) = (Executor::stdin(), Executor::stdout());
+                    Executor::set_stdin(console_id, stdin);
+                    Executor::set_stdout(console_id, stdout);
+                    let exit_code = self.exec_console_cmd(&args).await;
+                    Executor::set_stdin(console_id, old_stdin);
+                    Executor::set_stdout(console_id, old_stdout);
+                    Some(exit_code)
+                }
+                Ok(()) => {
+                    let pid = Self::spawn_cmd(args);
+                    // 保留退出状态直到作业被回收, 期间僵尸不会被丢弃
+                    Executor::hold(pid);
+                    Executor::set_stdin(pid, stdin);
+                    Executor::set_stdout(pid, stdout);
+                    if let Some(&pgid) = pids.first() {
+                        Executor::set_pgid(pid, pgid);
+                    }
+                    pids.push(pid);
+                    None
+                }
+                Err(e) => {
+                    println!("{}", e);
+                    Some(1)
+                }
+            };
+            if index + 1 == count {
+                last_exit_code = exit_code;
+            }
+        }
+
+        if pids.is_empty() {
+            return last_exit_code.unwrap_or(0);
+        }
+        let cmdline = texts.join(" | ");
+        if pipeline.background {
+            let pgid = pids[0];
+            let id = self.add_job(pids, cmdline, false);
+            println!("[{}] {}", id, pgid);
+            0
+        } else {
+            let exit_code = self.wait_foreground(pids, cmdline).await;
+            match last_exit_code {
+                Some(code) if exit_code != STOPPED_EXIT_CODE => code,
+                _ => exit_code,
+            }
+        }
+    }
+
+    // 打开命令的重定向文件, 替换标准输入输出
+    fn open_redirects(
+        &self,
+        command: &Command,
+        stdin: &mut Option<Stdin>,
+        stdout: &mut Option<Stdout>,
+    ) -> Result<()> {
+        for redirect in command.redirects.iter() {
+            let path = redirect.target.expand(|name| self.lookup_var(name));
+            let path = Fs::to_absolute_path(&path);
+            let mode = match redirect.kind {
+                RedirectKind::Input => "r",
+                RedirectKind::Output => "w",
+                RedirectKind::Append => "a",
+            };
+            let file = File::open(&path, mode).map_err(|e| anyhow!("{}: {}", path, e))?;
+            match redirect.kind {
+                RedirectKind::Input => *stdin = Some(Rc::new(RefCell::new(file))),
+                _ => *stdout = Some(Rc::new(RefCell::new(file))),
+            }
+        }
+        Ok(())
+    }
+
+    // 命令的显示文本, 用于作业列表
+    fn command_text(&self, command: &Command, args: &[String]) -> String {
+        let mut text = args.join(" ");
+        for redirect in command.redirects.iter() {
+            let op = match redirect.kind {
+                RedirectKind::Input => " < ",
+                RedirectKind::Output => " > ",
+                RedirectKind::Append => " >> ",
+            };
+            text.push_str(op);
+            text.push_str(&redirect.target.expand(|name| self.lookup_var(name)));
+        }
+        text
+    }
+
+    fn is_console_cmd(cmd: &str) -> bool {
+        CONSOLE_CMDS.contains(&cmd)
+    }
+
+    async fn exec_console_cmd(&mut self, args: &[String]) -> ExitCode {
+        match args[0].as_str() {
             // 显示帮助
-            if cmd == "help" || cmd == "?" {
+            "help" | "?" => {
                 println!("Available commands:");
+                for (cmd, desc) in CONSOLE_CMDS_HELP.iter() {
+                    println!("  {:<40} - {}", cmd, desc);
+                }
                 let parser_list = &self.cmds_parser_list;
                 for parser in parser_list.iter() {
                     let helps = parser.help();
@@ -196,39 +567,152 @@ impl Console {
                         println!("  {:<40} - {}", cmd, desc);
                     }
                 }
-                return;
+                0
             }
+            "set" => self.cmd_set(),
+            "unset" => self.cmd_unset(args),
+            "jobs" => self.cmd_jobs(),
+            "fg" => self.cmd_fg(args).await,
+            "bg" => self.cmd_bg(args),
+            _ => 127,
+        }
+    }
 
-            // 执行命令
-            let pid = Executor::spawn(
-                cmd.clone(),
-                Box::pin(async move {
-                    let parser_list = &Console::get_mut().cmds_parser_list;
-                    for parser in parser_list.iter() {
-                        let exit_code = parser.parse(&args).await;
-                        if exit_code != 127 {
-                            return exit_code;
-                        }
-                    }
-                    println!("Unknown command: {}", args.join(" "));
-                    127
-                }),
-            );
+    // 等待前台任务全部结束, 监听 Ctrl+C 终止, Ctrl+Z 挂起, 返回最后一个任务的退出码
+    async fn wait_foreground(&mut self, pids: Vec<TaskId>, cmdline: String) -> ExitCode {
+        let pgid = pids[0];
+        loop {
+            sys::sleep_ms(TTY_POLL_INTERVAL_MS).await;
 
-            // 等待前台任务结束, 监听 Ctrl+C 终止 
-            loop {
-                sys::yield_now().await;
+            if !pids.iter().any(|pid| Executor::is_running(*pid)) {
+                break;
+            }
 
-                if !Executor::is_running(pid) {
-                    break;
-                }
-                
-                // 监听 Ctrl+C 以终止前台任务
-                if  SimpleOs::tty().tty_get_break() {
-                    Executor::kill(pid);
-                }
+            // 监听 Ctrl+C, 向前台任务组发送 SIGINT, 由任务的信号处理器决定是否结束
+            if SimpleOs::tty().tty_get_break() {
+                Executor::send_group_signal(pgid, Signal::SIGINT);
+            }
+
+            // 监听 Ctrl+Z 以挂起前台任务, 转为作业
+            if SimpleOs::tty().tty_get_suspend() {
+                Executor::send_group_signal(pgid, Signal::SIGSTOP);
+                let id = self.add_job(pids, cmdline.clone(), true);
+                println!();
+                println!("[{}]+  Stopped\t\t{}", id, cmdline);
+                return STOPPED_EXIT_CODE;
+            }
+        }
+        Self::reap_all(&pids)
+    }
+
+    // 释放并回收已结束的任务, 返回最后一个任务的退出码
+    fn reap_all(pids: &[TaskId]) -> ExitCode {
+        let mut exit_code = None;
+        for pid in pids.iter() {
+            exit_code = Executor::release(*pid);
+        }
+        exit_code.unwrap_or(-1)
+    }
+
+    fn cmd_set(&mut self) -> ExitCode {
+        for (name, value) in self.vars.iter() {
+            println!("{}={}", name, value);
+        }
+        0
+    }
+
+    fn cmd_unset(&mut self, args: &[String]) -> ExitCode {
+        if args.len() < 2 {
+            println!("Usage: unset <name>");
+            return 2;
+        }
+        for name in args[1..].iter() {
+            self.vars.remove(name);
+        }
+        0
+    }
+
+    // 添加作业, 作业号为当前最大作业号加一, 返回作业号
+    fn add_job(&mut self, pids: Vec<TaskId>, cmdline: String, stopped: bool) -> u32 {
+        let id = self.jobs.iter().map(|job| job.id).max().unwrap_or(0) + 1;
+        self.jobs.push(Job {
+            id,
+            pgid: pids[0],
+            pids,
+            cmdline,
+            stopped,
+        });
+        id
+    }
+
+    // 查找作业, 未指定作业号时为最近的作业, 作业号可带 % 前缀
+    fn find_job(&self, args: &[String]) -> Option<usize> {
+        match args.get(1) {
+            Some(arg) => {
+                let id = arg.trim_start_matches('%').parse::<u32>().ok()?;
+                self.jobs.iter().position(|job| job.id == id)
+            }
+            None => self.jobs.len().checked_sub(1),
+        }
+    }
+
+    // 移除并回收已结束的作业, 报告其退出码
+    fn report_done_jobs(&mut self) {
+        self.jobs.retain(|job| {
+            if job.pids.iter().any(|pid| Executor::is_running(*pid)) {
+                return true;
+            }
+            match Self::reap_all(&job.pids) {
+                0 => println!("[{}]   Done\t\t{}", job.id, job.cmdline),
+                code => println!("[{}]   Exit {}\t\t{}", job.id, code, job.cmdline),
             }
+            false
+        });
+    }
+
+    fn cmd_jobs(&mut self) -> ExitCode {
+        self.report_done_jobs();
+        for job in self.jobs.iter() {
+            let state = if job.stopped { "Stopped" } else { "Running" };
+            println!("[{}] {}\t{}\t\t{}", job.id, job.pgid, state, job.cmdline);
         }
+        0
+    }
+
+    async fn cmd_fg(&mut self, args: &[String]) -> ExitCode {
+        self.report_done_jobs();
+        let index = match self.find_job(args) {
+            Some(index) => index,
+            None => {
+                println!("fg: no such job");
+                return 1;
+            }
+        };
+        let job = self.jobs.remove(index);
+        println!("{}", job.cmdline);
+        if job.stopped {
+            Executor::send_group_signal(job.pgid, Signal::SIGCONT);
+        }
+        SimpleOs::tty().tty_clear_rx();
+        self.wait_foreground(job.pids, job.cmdline).await
+    }
+
+    fn cmd_bg(&mut self, args: &[String]) -> ExitCode {
+        self.report_done_jobs();
+        let index = match self.find_job(args) {
+            Some(index) => index,
+            None => {
+                println!("bg: no such job");
+                return 1;
+            }
+        };
+        let job = &mut self.jobs[index];
+        if job.stopped {
+            job.stopped = false;
+            Executor::send_group_signal(job.pgid, Signal::SIGCONT);
+        }
+        println!("[{}]+ {} &", job.id, job.cmdline);
+        0
     }
 
     async fn try_parse_cmdline(&mut self) {
@@ -247,13 +731,21 @@ impl Console {
 
         // 添加到历史记录
         self.add_to_history(line_str.as_bytes());
-        let cmd_list = line_str.split(";");
-        for cmd in cmd_list {
-            let args: Vec<String> = cmd.split_whitespace().map(|s| s.to_string()).collect();
-            if args.len() > 0 {
-                self.exec_cmd(args).await;
+        let pipelines = match parse_cmdline(line_str) {
+            Ok(pipelines) => pipelines,
+            Err(e) => {
+                println!("{}", e);
+                self.last_exit_code = SYNTAX_ERROR_EXIT_CODE;
+                return;
+            }
+        };
+        for pipeline in pipelines.iter() {
+            if pipeline.condition.check(self.last_exit_code) {
+                self.last_exit_code = self.exec_pipeline(pipeline).await;
             }
         }
+        // 报告已结束的后台作业
+        self.report_done_jobs();
     }
 
     pub async fn start() -> ExitCode {
@@ -271,6 +763,8 @@ impl Console {
         loop {
             let c = SimpleOs::tty().tty_getc();
             if let Some(b) = c {
+                let double_tab = b == b'\t' && self.last_key_tab;
+                self.last_key_tab = b == b'\t';
                 match self.escape_state {
                     EscapeState::Normal => {
                         match b {
@@ -303,6 +797,10 @@ impl Console {
                             8 | 127 => {
                                 self.backspace();
                             }
+                            // Tab 补全
+                            b'\t' => {
+                                self.complete(double_tab);
+                            }
                             // ESC序列开始
                             27 => {
                                 self.escape_state = EscapeState::Escape;
@@ -367,24 +865,45 @@ impl Console {
                         self.escape_state = EscapeState::Normal;
                     }
                 }
+                sys::yield_now().await;
+            } else {
+                sys::sleep_ms(TTY_POLL_INTERVAL_MS).await;
             }
-            sys::yield_now().await;
         }
     }
 
     pub async fn getc() -> u8 {
+        // 标准输入被重定向时从输入流读取, 输入结束时返回 EOT (Ctrl+D)
+        if sys::is_stdin_redirected() {
+            let mut byte = [0u8; 1];
+            return match sys::stdin_read(&mut byte).await {
+                0 => EOT,
+                _ => byte[0],
+            };
+        }
         let console = Console::get_mut();
         loop {
             if let Some(b) = SimpleOs::tty().tty_getc() {
                 return b;
             }
-            sys::yield_now().await;
+            sys::sleep_ms(TTY_POLL_INTERVAL_MS).await;
         }
     }
 
     pub async fn readline(buffer: &mut [u8]) -> usize {
         let console = Console::get_mut();
         let mut index = 0usize;
+        // 标准输入被重定向时从输入流读取, 输入结束时返回已读取的内容
+        if sys::is_stdin_redirected() {
+            let mut byte = [0u8; 1];
+            while sys::stdin_read(&mut byte).await != 0 && byte[0] != b'\n' {
+                if byte[0] != b'\r' && index < buffer.len() {
+                    buffer[index] = byte[0];
+                    index += 1;
+                }
+            }
+            return index;
+        }
         loop {
             if let Some(b) = SimpleOs::tty().tty_getc() {
                 if b == b'\r' || b == b'\n' {
@@ -394,8 +913,10 @@ impl Console {
                     buffer[index] = b;
                     index += 1;
                 }
+                sys::yield_now().await;
+            } else {
+                sys::sleep_ms(TTY_POLL_INTERVAL_MS).await;
             }
-            sys::yield_now().await;
         }
         index
     }
@@ -0,0 +1,729 @@
+use crate::executor::ExitCode;
+use alloc::string::String;
+use alloc::vec::Vec;
+use anyhow::{anyhow, Result};
+use core::fmt;
+use core::iter::Peekable;
+use core::str::Chars;
+
+/// 单词的组成部分, 变量在命令执行时才展开
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum WordPart {
+    Unquoted(String), // 未加引号的文本, 转义已处理
+    Quoted(String),   // 单引号或双引号中的文本
+    Var(String),      // 变量引用, $? 的变量名为 "?"
+}
+
+/// 命令行中的一个单词, 由多个部分拼接而成, 例如 `a"b c"$X`
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Word {
+    pub parts: Vec<WordPart>,
+}
+
+impl Word {
+    fn push(&mut self, c: char, quoted: bool) {
+        match (self.parts.last_mut(), quoted) {
+            (Some(WordPart::Unquoted(text)), false) | (Some(WordPart::Quoted(text)), true) => {
+                text.push(c)
+            }
+            _ => {
+                let text = String::from(c);
+                self.parts.push(if quoted {
+                    WordPart::Quoted(text)
+                } else {
+                    WordPart::Unquoted(text)
+                });
+            }
+        }
+    }
+
+    // 引号开始, 保证 "" 也能产生一个空单词
+    fn begin_quote(&mut self) {
+        if !matches!(self.parts.last(), Some(WordPart::Quoted(_))) {
+            self.parts.push(WordPart::Quoted(String::new()));
+        }
+    }
+
+    /// 展开变量, 未定义的变量展开为空; 展开结果不再拆分为多个单词
+    pub fn expand(&self, lookup: impl Fn(&str) -> Option<String>) -> String {
+        let mut result = String::new();
+        for part in self.parts.iter() {
+            match part {
+                WordPart::Unquoted(text) | WordPart::Quoted(text) => result.push_str(text),
+                WordPart::Var(name) => {
+                    if let Some(value) = lookup(name) {
+                        result.push_str(&value);
+                    }
+                }
+            }
+        }
+        result
+    }
+
+    /// 未加引号的 `NAME=value` 形式视为变量赋值, 返回 (变量名, 值)
+    pub fn as_assignment(&self) -> Option<(String, Word)> {
+        let first = match self.parts.first() {
+            Some(WordPart::Unquoted(text)) => text,
+            _ => return None,
+        };
+        let (name, rest) = first.split_once('=')?;
+        if !is_var_name(name) {
+            return None;
+        }
+        let mut value = Word::default();
+        if !rest.is_empty() {
+            value.parts.push(WordPart::Unquoted(String::from(rest)));
+        }
+        value.parts.extend(self.parts[1..].iter().cloned());
+        Some((String::from(name), value))
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Token {
+    Word(Word),
+    Semicolon, // ;
+    Ampersand, // &
+    AndIf,          // &&
+    OrIf,           // ||
+    Pipe,           // |
+    RedirectIn,     // <
+    RedirectOut,    // >
+    RedirectAppend, // >>
+}
+
+impl fmt::Display for Token {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Token::Word(word) => write!(f, "{}", word.expand(|_| None)),
+            Token::Semicolon => write!(f, ";"),
+            Token::Ampersand => write!(f, "&"),
+            Token::AndIf => write!(f, "&&"),
+            Token::OrIf => write!(f, "||"),
+            Token::Pipe => write!(f, "|"),
+            Token::RedirectIn => write!(f, "<"),
+            Token::RedirectOut => write!(f, ">"),
+            Token::RedirectAppend => write!(f, ">>"),
+        }
+    }
+}
+
+/// 命令的执行条件, 由其前面的操作符决定
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Condition {
+    Always,    // 第一条命令或在 ; & 之后
+    IfSuccess, // 在 && 之后, 上一条命令退出码为 0 时执行
+    IfFailure, // 在 || 之后, 上一条命令退出码不为 0 时执行
+}
+
+impl Condition {
+    /// 根据上一条命令的退出码判断是否执行, 跳过的命令不改变退出码
+    pub fn check(&self, last_exit_code: ExitCode) -> bool {
+        match self {
+            Condition::Always => true,
+            Condition::IfSuccess => last_exit_code == 0,
+            Condition::IfFailure => last_exit_code != 0,
+        }
+    }
+}
+
+/// 重定向方式
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum RedirectKind {
+    Input,  // < 从文件读取标准输入
+    Output, // > 标准输出写入文件, 覆盖原内容
+    Append, // >> 标准输出追加到文件末尾
+}
+
+/// 重定向, 目标文件名在执行时展开
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Redirect {
+    pub kind: RedirectKind,
+    pub target: Word,
+}
+
+/// 一条简单命令
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Command {
+    pub words: Vec<Word>,
+    pub redirects: Vec<Redirect>, // 按出现顺序, 同类重定向以最后一个为准
+}
+
+/// 管道: 以 `|` 连接的命令, 前一条命令的标准输出作为后一条命令的标准输入
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Pipeline {
+    pub commands: Vec<Command>,
+    pub condition: Condition,
+    pub background: bool, // 以 & 结尾, 在后台执行
+}
+
+fn is_var_name(name: &str) -> bool {
+    let mut chars = name.chars();
+    matches!(chars.next(), Some(c) if c.is_ascii_alphabetic() || c == '_')
+        && chars.all(|c| c.is_ascii_alphanumeric() || c == '_')
+}
+
+// 解析 $ 之后的变量引用: $NAME, ${NAME}, $?, 其他情况 $ 为普通字符
+fn lex_var(chars: &mut Peekable<Chars>, word: &mut Word, quoted: bool) -> Result<()> {
+    match chars.peek() {
+        Some('?') => {
+            chars.next();
+            word.parts.push(WordPart::Var(String::from("?")));
+        }
+        Some('{') => {
+            chars.next();
+            let mut name = String::new();
+            loop {
+                match chars.next() {
+                    Some('}') => break,
+                    Some(c) => name.push(c),
+                    None => return Err(anyhow!("syntax error: unterminated '${{'")),
+                }
+            }
+            if name != "?" && !is_var_name(&name) {
+                return Err(anyhow!("syntax error: bad substitution '${{{}}}'", name));
+            }
+            word.parts.push(WordPart::Var(name));
+        }
+        Some(&c) if c.is_ascii_alphabetic() || c == '_' => {
+            let mut name = String::new();
+            while let Some(&c) = chars.peek() {
+                if !(c.is_ascii_alphanumeric() || c == '_') {
+                    break;
+                }
+                name.push(c);
+                chars.next();
+            }
+            word.parts.push(WordPart::Var(name));
+        }
+        _ => word.push('$', quoted),
+    }
+    Ok(())
+}
+
+/// 将命令行拆分为单词和操作符
+///
+/// - 单引号内的内容原样保留
+/// - 双引号内展开变量, `\` 只转义 `$ " \ ``, 其他情况保留 `\`
+/// - 引号外 `\` 转义下一个字符
+/// - 单词开头的 `#` 到行尾为注释
+pub fn tokenize_cmdline(line: &str) -> Result<Vec<Token>> {
+    let mut tokens = Vec::new();
+    let mut chars = line.chars().peekable();
+    let mut word: Option<Word> = None;
+
+    fn finish_word(tokens: &mut Vec<Token>, word: &mut Option<Word>) {
+        if let Some(word) = word.take() {
+            tokens.push(Token::Word(word));
+        }
+    }
+
+    while let Some(c) = chars.next() {
+        match c {
+            c if c.is_whitespace() => finish_word(&mut tokens, &mut word),
+            '#' if word.is_none() => break,
+            ';' => {
+                finish_word(&mut tokens, &mut word);
+                tokens.push(Token::Semicolon);
+            }
+            '&' => {
+                finish_word(&mut tokens, &mut word);
+                if chars.next_if_eq(&'&').is_some() {
+                    tokens.push(Token::AndIf);
+                } else {
+                    tokens.push(Token::Ampersand);
+                }
+            }
+            '|' => {
+                finish_word(&mut tokens, &mut word);
+                if chars.next_if_eq(&'|').is_some() {
+                    tokens.push(Token::OrIf);
+                } else {
+                    tokens.push(Token::Pipe);
+                }
+            }
+            '<' => {
+                finish_word(&mut tokens, &mut word);
+                tokens.push(Token::RedirectIn);
+            }
+            '>' => {
+                finish_word(&mut tokens, &mut word);
+                if chars.next_if_eq(&'>').is_some() {
+                    tokens.push(Token::RedirectAppend);
+                } else {
+                    tokens.push(Token::RedirectOut);
+                }
+            }
+            '\\' => match chars.next() {
+                Some(c) => word.get_or_insert_default().push(c, false),
+                None => return Err(anyhow!("syntax error: unexpected end of line after '\\'")),
+            },
+            '\'' => {
+                let word = word.get_or_insert_default();
+                word.begin_quote();
+                loop {
+                    match chars.next() {
+                        Some('\'') => break,
+                        Some(c) => word.push(c, true),
+                        None => return Err(anyhow!("syntax error: unterminated quote '")),
+                    }
+                }
+            }
+            '"' => {
+                let word = word.get_or_insert_default();
+                word.begin_quote();
+                loop {
+                    match chars.next() {
+                        Some('"') => break,
+                        Some('\\') => match chars.peek() {
+                            Some(&c) if matches!(c, '$' | '"' | '\\' | '`') => {
+                                chars.next();
+                                word.push(c, true);
+                            }
+                            _ => word.push('\\', true),
+                        },
+                        Some('$') => lex_var(&mut chars, word, true)?,
+                        Some(c) => word.push(c, true),
+                        None => return Err(anyhow!("syntax error: unterminated quote \"")),
+                    }
+                }
+            }
+            '$' => lex_var(&mut chars, word.get_or_insert_default(), false)?,
+            c => word.get_or_insert_default().push(c, false),
+        }
+    }
+    finish_word(&mut tokens, &mut word);
+    Ok(tokens)
+}
+
+/// 解析命令行为管道列表, 管道之间以 `;` `&` `&&` `||` 分隔
+///
+/// `&&` 和 `||` 连接的管道按从左到右的顺序依次判断是否执行;
+/// 不支持将 `&&` `||` 连接的整个序列放到后台
+pub fn parse_cmdline(line: &str) -> Result<Vec<Pipeline>> {
+    let mut pipelines = Vec::new();
+    let mut commands = Vec::new();
+    let mut command = Command::default();
+    let mut condition = Condition::Always;
+    let mut chained = false; // 当前序列中是否有 && 或 ||
+    let mut last_token = None;
+    let mut tokens = tokenize_cmdline(line)?.into_iter();
+    while let Some(token) = tokens.next() {
+        let kind = match token {
+            Token::Word(word) => {
+                command.words.push(word);
+                continue;
+            }
+            Token::RedirectIn => Some(RedirectKind::Input),
+            Token::RedirectOut => Some(RedirectKind::Output),
+            Token::RedirectAppend => Some(RedirectKind::Append),
+            _ => None,
+        };
+        if let Some(kind) = kind {
+            match tokens.next() {
+                Some(Token::Word(target)) => command.redirects.push(Redirect { kind, target }),
+                Some(next) => return Err(anyhow!("syntax error near unexpected token '{}'", next)),
+                None => return Err(anyhow!("syntax error: unexpected end of line after '{}'", token)),
+            }
+            continue;
+        }
+        if command.words.is_empty() {
+            return Err(anyhow!("syntax error near unexpected token '{}'", token));
+        }
+        commands.push(core::mem::take(&mut command));
+        if token == Token::Pipe {
+            last_token = Some(token);
+            continue;
+        }
+        if token == Token::Ampersand && chained {
+            return Err(anyhow!("syntax error: '&' after '&&' or '||' is not supported"));
+        }
+        pipelines.push(Pipeline {
+            commands: core::mem::take(&mut commands),
+            condition,
+            background: token == Token::Ampersand,
+        });
+        (condition, chained) = match token {
+            Token::AndIf => (Condition::IfSuccess, true),
+            Token::OrIf => (Condition::IfFailure, true),
+            _ => (Condition::Always, false),
+        };
+        last_token = Some(token);
+    }
+    if !command.words.is_empty() {
+        commands.push(command);
+        pipelines.push(Pipeline {
+            commands,
+            condition,
+            background: false,
+        });
+    } else if !command.redirects.is_empty() {
+        return Err(anyhow!("syntax error: missing command before redirection"));
+    } else if let Some(token @ (Token::AndIf | Token::OrIf | Token::Pipe)) = last_token {
+        return Err(anyhow!("syntax error: unexpected end of line after '{}'", token));
+    }
+    Ok(pipelines)
+}
+
+/// 将参数转为命令行中的一个单词, 经 `tokenize_cmdline` 解析后还原为原参数
+///
+/// 只含普通字符的参数原样返回, 否则用单引号包围, 参数中的 `'` 写为 `'\''`
+pub fn quote_word(arg: &str) -> String {
+    let plain = !arg.is_empty()
+        && !arg.starts_with('#')
+        && arg
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || "-_./:=,+@%^".contains(c));
+    if plain {
+        return String::from(arg);
+    }
+    let mut quoted = String::from("'");
+    for c in arg.chars() {
+        match c {
+            '\'' => quoted.push_str("'\\''"),
+            _ => quoted.push(c),
+        }
+    }
+    quoted.push('\'');
+    quoted
+}
+
+/// 光标前的部分命令行, 用于 Tab 补全
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct PartialLine {
+    pub args: Vec<String>,    // 当前命令中光标前已完整的参数, 不含重定向目标, 变量不展开
+    pub word: String,         // 光标处未完成的单词, 已去掉引号和转义
+    pub quote: Option<char>,  // 单词中未闭合的引号
+    pub after_redirect: bool, // 单词是重定向的目标
+    pub in_comment: bool,     // 光标位于注释中
+}
+
+/// 按 `tokenize_cmdline` 的引号和转义规则拆分光标前的部分命令行, 不报告语法错误
+pub fn parse_partial_cmdline(line: &str) -> PartialLine {
+    let mut partial = PartialLine::default();
+    let mut chars = line.chars().peekable();
+    let mut word: Option<String> = None;
+    let mut redirect = false; // 下一个单词是重定向目标
+
+    fn finish_word(partial: &mut PartialLine, word: &mut Option<String>, redirect: &mut bool) {
+        if let Some(word) = word.take() {
+            if !*redirect {
+                partial.args.push(word);
+            }
+            *redirect = false;
+        }
+    }
+
+    while let Some(c) = chars.next() {
+        match c {
+            c if c.is_whitespace() => finish_word(&mut partial, &mut word, &mut redirect),
+            '#' if word.is_none() => {
+                partial.in_comment = true;
+                return partial;
+            }
+            ';' | '&' | '|' => {
+                // 新的命令
+                word = None;
+                redirect = false;
+                partial.args.clear();
+            }
+            '<' | '>' => {
+                finish_word(&mut partial, &mut word, &mut redirect);
+                chars.next_if_eq(&'>');
+                redirect = true;
+            }
+            '\\' => {
+                if let Some(c) = chars.next() {
+                    word.get_or_insert_default().push(c);
+                }
+            }
+            '\'' => {
+                let word = word.get_or_insert_default();
+                loop {
+                    match chars.next() {
+                        Some('\'') => break,
+                        Some(c) => word.push(c),
+                        None => {
+                            partial.quote = Some('\'');
+                            break;
+                        }
+                    }
+                }
+            }
+            '"' => {
+                let word = word.get_or_insert_default();
+                loop {
+                    match chars.next() {
+                        Some('"') => break,
+                        Some('\\') => match chars.peek() {
+                            Some(&c) if matches!(c, '$' | '"' | '\\' | '`') => {
+                                chars.next();
+                                word.push(c);
+                            }
+                            _ => word.push('\\'),
+                        },
+                        Some(c) => word.push(c),
+                        None => {
+                            partial.quote = Some('"');
+                            break;
+                        }
+                    }
+                }
+            }
+            c => word.get_or_insert_default().push(c),
+        }
+    }
+    partial.word = word.unwrap_or_default();
+    partial.after_redirect = redirect;
+    partial
+}
+
+/// 转义补全插入的文本, 使其在 quote 指定的引号状态下被解析为原文
+pub fn escape_partial(text: &str, quote: Option<char>) -> String {
+    let mut escaped = String::new();
+    for c in text.chars() {
+        match quote {
+            Some('\'') if c == '\'' => escaped.push_str("'\\''"),
+            Some('\'') => escaped.push(c),
+            Some(_) if matches!(c, '$' | '"' | '\\' | '`') => {
+                escaped.push('\\');
+                escaped.push(c);
+            }
+            Some(_) => escaped.push(c),
+            None if c.is_whitespace() || "\\'\";&|<>$#".contains(c) => {
+                escaped.push('\\');
+                escaped.push(c);
+            }
+            None => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::format;
+    use alloc::string::ToString;
+    use alloc::vec;
+
+    fn lookup(name: &str) -> Option<String> {
+        match name {
+            "X" => Some("1".to_string()),
+            "?" => Some("3".to_string()),
+            _ => None,
+        }
+    }
+
+    // 拆分并展开单词, 遇到操作符时 panic
+    fn words(line: &str) -> Vec<String> {
+        tokenize_cmdline(line)
+            .unwrap()
+            .into_iter()
+            .map(|token| match token {
+                Token::Word(word) => word.expand(lookup),
+                token => panic!("unexpected token '{}'", token),
+            })
+            .collect()
+    }
+
+    fn args(command: &Command) -> Vec<String> {
+        command.words.iter().map(|word| word.expand(lookup)).collect()
+    }
+
+    #[test]
+    fn split_whitespace() {
+        assert_eq!(words("  a  b\tc  "), vec!["a", "b", "c"]);
+        assert!(words("   ").is_empty());
+    }
+
+    #[test]
+    fn quotes() {
+        assert_eq!(words(r#"'a b' "c d" e'f'g "" ''"#), vec!["a b", "c d", "efg", "", ""]);
+        // 单引号内原样保留
+        assert_eq!(words(r#"'$X \n "'"#), vec![r#"$X \n ""#]);
+        // 引号内的操作符不拆分
+        assert_eq!(words(r#""a;b|c&&d>e" 'x # y'"#), vec!["a;b|c&&d>e", "x # y"]);
+    }
+
+    #[test]
+    fn escapes() {
+        assert_eq!(words(r"a\ b \; \$X \\ \'"), vec!["a b", ";", "$X", "\\", "'"]);
+        // 双引号内只转义 $ " \ `, 其他情况保留反斜杠
+        assert_eq!(words(r#""\$X\"\\\a\`""#), vec![r#"$X"\\a`"#]);
+    }
+
+    #[test]
+    fn variables() {
+        assert_eq!(
+            words(r#"$X ${X}y $Xy "$X-$X" $UNDEF. a$X"#),
+            vec!["1", "1y", "", "1-1", ".", "a1"]
+        );
+        assert_eq!(words("$? ${?} \"$?\""), vec!["3", "3", "3"]);
+        // 不构成变量引用的 $ 为普通字符
+        assert_eq!(words("$ $1 a$ '$X'"), vec!["$", "$1", "a$", "$X"]);
+        // 展开结果不再拆分为多个单词
+        let word = match &tokenize_cmdline("$V").unwrap()[0] {
+            Token::Word(word) => word.clone(),
+            _ => unreachable!(),
+        };
+        assert_eq!(word.expand(|_| Some("a b".to_string())), "a b");
+    }
+
+    #[test]
+    fn comments() {
+        assert_eq!(words("echo a # comment ; b"), vec!["echo", "a"]);
+        assert_eq!(words("echo a#b '#c' \\#d"), vec!["echo", "a#b", "#c", "#d"]);
+        assert!(words("# only a comment").is_empty());
+    }
+
+    #[test]
+    fn operators() {
+        let tokens = tokenize_cmdline("a;b&c&&d||e|f<g>h>>i").unwrap();
+        let text: Vec<String> = tokens.iter().map(|token| token.to_string()).collect();
+        assert_eq!(
+            text,
+            vec![
+                "a", ";", "b", "&", "c", "&&", "d", "||", "e", "|", "f", "<", "g", ">", "h", ">>", "i"
+            ]
+        );
+    }
+
+    #[test]
+    fn assignment() {
+        let tokens = tokenize_cmdline(r#"A=x"$X" 1A=b "B=c""#).unwrap();
+        let assignments: Vec<Option<(String, String)>> = tokens
+            .iter()
+            .map(|token| match token {
+                Token::Word(word) => word
+                    .as_assignment()
+                    .map(|(name, value)| (name, value.expand(lookup))),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(
+            assignments,
+            vec![Some(("A".to_string(), "x1".to_string())), None, None]
+        );
+    }
+
+    #[test]
+    fn parse_pipelines() {
+        let pipelines = parse_cmdline("a | b > f && c || d < g; e >> h &").unwrap();
+        assert_eq!(pipelines.len(), 4);
+
+        assert_eq!(pipelines[0].condition, Condition::Always);
+        assert!(!pipelines[0].background);
+        assert_eq!(pipelines[0].commands.len(), 2);
+        assert_eq!(args(&pipelines[0].commands[0]), vec!["a"]);
+        assert_eq!(args(&pipelines[0].commands[1]), vec!["b"]);
+        assert_eq!(pipelines[0].commands[1].redirects[0].kind, RedirectKind::Output);
+
+        assert_eq!(pipelines[1].condition, Condition::IfSuccess);
+        assert_eq!(args(&pipelines[1].commands[0]), vec!["c"]);
+
+        assert_eq!(pipelines[2].condition, Condition::IfFailure);
+        assert!(!pipelines[2].background);
+        assert_eq!(pipelines[2].commands[0].redirects[0].kind, RedirectKind::Input);
+
+        assert_eq!(pipelines[3].condition, Condition::Always);
+        assert!(pipelines[3].background);
+        assert_eq!(pipelines[3].commands[0].redirects[0].kind, RedirectKind::Append);
+        assert_eq!(pipelines[3].commands[0].redirects[0].target.expand(lookup), "h");
+
+        // 重定向可以出现在命令中的任意位置
+        let pipelines = parse_cmdline("> f cat").unwrap();
+        assert_eq!(args(&pipelines[0].commands[0]), vec!["cat"]);
+        assert!(parse_cmdline("").unwrap().is_empty());
+        assert!(parse_cmdline("  # comment").unwrap().is_empty());
+    }
+
+    #[test]
+    fn condition_check() {
+        assert!(Condition::Always.check(1));
+        assert!(Condition::IfSuccess.check(0));
+        assert!(!Condition::IfSuccess.check(1));
+        assert!(Condition::IfFailure.check(-1));
+        assert!(!Condition::IfFailure.check(0));
+    }
+
+    #[test]
+    fn quote_word_roundtrip() {
+        let original = [
+            "plain", "/data/a.txt", "hello world", "it's", "$X", "", "#c", "a;b", "\\n", "\"q\"",
+        ];
+        let line: Vec<String> = original.iter().map(|arg| quote_word(arg)).collect();
+        assert_eq!(line[0], "plain");
+        assert_eq!(line[1], "/data/a.txt");
+        assert_eq!(words(&line.join(" ")), original);
+    }
+
+    #[test]
+    fn partial_line() {
+        let partial = parse_partial_cmdline(r"cat a\ b my\ d");
+        assert_eq!(partial.args, vec!["cat", "a b"]);
+        assert_eq!(partial.word, "my d");
+        assert_eq!(partial.quote, None);
+
+        let partial = parse_partial_cmdline(r#"ls x; cat 'my dir/f"#);
+        assert_eq!(partial.args, vec!["cat"]);
+        assert_eq!(partial.word, "my dir/f");
+        assert_eq!(partial.quote, Some('\''));
+
+        let partial = parse_partial_cmdline(r#"cat "a\"b"#);
+        assert_eq!(partial.word, "a\"b");
+        assert_eq!(partial.quote, Some('"'));
+
+        let partial = parse_partial_cmdline("ps > /data/p");
+        assert_eq!(partial.args, vec!["ps"]);
+        assert_eq!(partial.word, "/data/p");
+        assert!(partial.after_redirect);
+
+        // 重定向目标不计入参数
+        let partial = parse_partial_cmdline("grep x < in.txt ");
+        assert_eq!(partial.args, vec!["grep", "x"]);
+        assert_eq!(partial.word, "");
+        assert!(!partial.after_redirect);
+
+        let partial = parse_partial_cmdline("a && b |c");
+        assert!(partial.args.is_empty());
+        assert_eq!(partial.word, "c");
+
+        assert!(parse_partial_cmdline("echo # c").in_comment);
+        assert!(!parse_partial_cmdline("echo a#c").in_comment);
+    }
+
+    #[test]
+    fn escape_partial_roundtrip() {
+        let text = "my dir/it's $X \"q\" a;b\\";
+        for (open, quote) in [("", None), ("'", Some('\'')), ("\"", Some('"'))] {
+            let partial_line = format!("{}{}", open, escape_partial(text, quote));
+            assert_eq!(words(&format!("{}{}", partial_line, open)), vec![text]);
+            let partial = parse_partial_cmdline(&partial_line);
+            assert_eq!(partial.word, text);
+            assert_eq!(partial.quote, quote);
+        }
+    }
+
+    #[test]
+    fn syntax_errors() {
+        let error = |line: &str| parse_cmdline(line).unwrap_err().to_string();
+        assert_eq!(error("'abc"), "syntax error: unterminated quote '");
+        assert_eq!(error("\"abc"), "syntax error: unterminated quote \"");
+        assert_eq!(error("a \\"), "syntax error: unexpected end of line after '\\'");
+        assert_eq!(error("echo ${X"), "syntax error: unterminated '${'");
+        assert_eq!(error("echo ${1x}"), "syntax error: bad substitution '${1x}'");
+        assert_eq!(error("| a"), "syntax error near unexpected token '|'");
+        assert_eq!(error(";"), "syntax error near unexpected token ';'");
+        assert_eq!(error("a && && b"), "syntax error near unexpected token '&&'");
+        assert_eq!(error("a ||"), "syntax error: unexpected end of line after '||'");
+        assert_eq!(error("a |"), "syntax error: unexpected end of line after '|'");
+        assert_eq!(error("a >"), "syntax error: unexpected end of line after '>'");
+        assert_eq!(error("a > | b"), "syntax error near unexpected token '|'");
+        assert_eq!(error("> f"), "syntax error: missing command before redirection");
+        assert_eq!(
+            error("a && b &"),
+            "syntax error: '&' after '&&' or '||' is not supported"
+        );
+    }
+}
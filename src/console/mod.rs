@@ -1,9 +1,11 @@
 mod cmd_parser;
 mod console;
+mod lexer;
 mod builtin_cmds;
 
 pub use cmd_parser::*;
 pub use console::*;
+pub use lexer::*;
 
 #[allow(unused)]
 pub use builtin_cmds::*;
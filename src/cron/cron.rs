@@ -0,0 +1,282 @@
+use alloc::boxed::Box;
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+use anyhow::{anyhow, Result};
+use chrono::Timelike;
+
+use crate::console::Console;
+use crate::cron::CronExpr;
+use crate::driver::fs::File;
+use crate::driver::rtc::RtcDriver;
+use crate::executor::{Executor, ExitCode, Runnable, TaskId};
+use crate::{println, singleton, sys};
+
+const RTC_RETRY_MS: u32 = 1000; // 读取 RTC 失败时的重试间隔
+
+/// 定时执行的内容
+pub enum CronTask {
+    /// 控制台命令行, 会被保存到任务表文件
+    Command(String),
+    /// 代码中注册的 Runnable, 不会被保存
+    Runnable(Runnable, Vec<String>),
+}
+
+struct CronJob {
+    id: u32,
+    spec: String, // 原始的 cron 表达式
+    expr: CronExpr,
+    task: CronTask,
+}
+
+/// 定时任务信息, 用于显示
+#[derive(Clone, Debug)]
+pub struct CronJobInfo {
+    pub id: u32,
+    pub spec: String,
+    pub task: String,      // 命令行或 Runnable 名称
+    pub persistent: bool,  // 是否保存在任务表文件中
+}
+
+/// 按 RTC 墙上时间执行定时任务的服务, 类似 cron
+///
+/// 任务表文件每行一个任务: `<cron 表达式> <命令行>`, # 开头的行为注释
+pub struct Cron {
+    rtc: Option<&'static mut dyn RtcDriver>,
+    jobs: Vec<CronJob>,
+    next_id: u32,
+    table_path: Option<String>,
+}
+
+singleton!(Cron {
+    rtc: None,
+    jobs: Vec::new(),
+    next_id: 1,
+    table_path: None,
+});
+
+impl Cron {
+    /// 启动定时任务服务, 从 table_path 加载任务表, 返回服务任务ID
+    ///
+    /// table_path 为 None 时不保存任务表
+    pub fn start(rtc: &'static mut dyn RtcDriver, table_path: Option<&str>) -> TaskId {
+        let cron = Cron::get_mut();
+        cron.rtc = Some(rtc);
+        cron.table_path = table_path.map(|path| path.to_string());
+        if let Err(e) = Self::load() {
+            println!("crond: {}", e);
+        }
+        Executor::spawn("crond", Box::pin(Self::run()))
+    }
+
+    /// 添加控制台命令行任务并保存任务表, 返回任务ID
+    ///
+    /// 保存失败时撤销添加, 内存中的任务与任务表保持一致
+    pub fn add_command(spec: &str, cmdline: &str) -> Result<u32> {
+        let cmdline = cmdline.trim();
+        if cmdline.is_empty() {
+            return Err(anyhow!("empty command"));
+        }
+        let id = Self::add(spec, CronTask::Command(cmdline.to_string()))?;
+        if let Err(e) = Self::save() {
+            Cron::get_mut().jobs.retain(|job| job.id != id);
+            return Err(e);
+        }
+        Ok(id)
+    }
+
+    /// 添加 Runnable 任务, 不会被保存, 返回任务ID
+    pub fn add_runnable(spec: &str, runnable: Runnable, args: Vec<String>) -> Result<u32> {
+        Self::add(spec, CronTask::Runnable(runnable, args))
+    }
+
+    fn add(spec: &str, task: CronTask) -> Result<u32> {
+        let expr = CronExpr::parse(spec)?;
+        let cron = Cron::get_mut();
+        let id = cron.next_id;
+        cron.next_id = cron.next_id.wrapping_add(1);
+        cron.jobs.push(CronJob {
+            id,
+            spec: spec.trim().to_string(),
+            expr,
+            task,
+        });
+        Ok(id)
+    }
+
+    /// 删除任务, 删除命令行任务时保存任务表, 保存失败时撤销删除
+    pub fn remove(id: u32) -> Result<bool> {
+        let cron = Cron::get_mut();
+        let index = match cron.jobs.iter().position(|job| job.id == id) {
+            Some(index) => index,
+            None => return Ok(false),
+        };
+        let job = cron.jobs.remove(index);
+        if let CronTask::Command(_) = job.task {
+            if let Err(e) = Self::save() {
+                Cron::get_mut().jobs.insert(index, job);
+                return Err(e);
+            }
+        }
+        Ok(true)
+    }
+
+    pub fn jobs() -> Vec<CronJobInfo> {
+        Cron::get_mut()
+            .jobs
+            .iter()
+            .map(|job| CronJobInfo {
+                id: job.id,
+                spec: job.spec.clone(),
+                task: match &job.task {
+                    CronTask::Command(cmdline) => cmdline.clone(),
+                    CronTask::Runnable(runnable, args) => {
+                        let mut task = runnable.get_name();
+                        for arg in args.iter() {
+                            task.push(' ');
+                            task.push_str(arg);
+                        }
+                        task
+                    }
+                },
+                persistent: matches!(job.task, CronTask::Command(_)),
+            })
+            .collect()
+    }
+
+    /// 拆分任务表中的一行, 返回 (cron 表达式, 命令行)
+    pub fn split_line(line: &str) -> Option<(String, String)> {
+        let line = line.trim();
+        let fields = if line.starts_with('@') { 1 } else { 5 };
+        let mut rest = line;
+        let mut spec = Vec::new();
+        for _ in 0..fields {
+            rest = rest.trim_start();
+            let end = rest.find(char::is_whitespace)?;
+            spec.push(&rest[..end]);
+            rest = &rest[end..];
+        }
+        Some((spec.join(" "), rest.trim().to_string()))
+    }
+
+    // 从任务表文件加载命令行任务
+    fn load() -> Result<()> {
+        let path = match Cron::get_mut().table_path.as_ref() {
+            Some(path) => path.clone(),
+            None => return Ok(()),
+        };
+        // 任务表文件不存在时视为空表
+        let mut file = match File::open(&path, "r") {
+            Ok(file) => file,
+            Err(_) => return Ok(()),
+        };
+        let mut content = Vec::new();
+        let mut buffer = [0u8; 128];
+        loop {
+            match file.read(&mut buffer)? {
+                0 => break,
+                n => content.extend_from_slice(&buffer[..n]),
+            }
+        }
+        file.close()?;
+
+        let content = core::str::from_utf8(&content).map_err(|_| anyhow!("invalid crontab {}", path))?;
+        for (line_no, line) in content.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let result = match Self::split_line(line) {
+                Some((spec, cmdline)) if !cmdline.is_empty() => {
+                    Self::add(&spec, CronTask::Command(cmdline))
+                }
+                _ => Err(anyhow!("missing command")),
+            };
+            if let Err(e) = result {
+                println!("crond: {}:{}: {}", path, line_no + 1, e);
+            }
+        }
+        Ok(())
+    }
+
+    // 保存命令行任务到任务表文件
+    fn save() -> Result<()> {
+        let cron = Cron::get_mut();
+        let path = match cron.table_path.as_ref() {
+            Some(path) => path,
+            None => return Ok(()),
+        };
+        let mut content = String::new();
+        for job in cron.jobs.iter() {
+            if let CronTask::Command(cmdline) = &job.task {
+                content.push_str(&job.spec);
+                content.push(' ');
+                content.push_str(cmdline);
+                content.push('\n');
+            }
+        }
+        let mut file = File::open(path, "w")?;
+        file.write(content.as_bytes())?;
+        file.close()
+    }
+
+    // 执行到期的任务, 命令行解析失败时返回 None
+    fn run_job(job: &CronJob) -> Option<TaskId> {
+        match &job.task {
+            CronTask::Command(cmdline) => match Console::parse_args(cmdline) {
+                Ok(args) => Some(Console::spawn_cmd(args)),
+                Err(e) => {
+                    println!("crond: job {}: {}", job.id, e);
+                    None
+                }
+            },
+            CronTask::Runnable(runnable, args) => {
+                Some(Executor::spawn(runnable.get_name(), runnable.run(args)))
+            }
+        }
+    }
+
+    // 服务任务: 每分钟读取一次 RTC, 执行匹配的任务
+    async fn run() -> ExitCode {
+        let mut last_minute = None;
+        let mut running: Vec<TaskId> = Vec::new();
+        loop {
+            let now = match Cron::get_mut().rtc.as_mut().map(|rtc| rtc.rtc_read_datetime()) {
+                Some(Ok(now)) => now,
+                Some(Err(e)) => {
+                    println!("crond: read rtc failed: {}", e);
+                    sys::sleep_ms(RTC_RETRY_MS).await;
+                    continue;
+                }
+                None => return 1,
+            };
+
+            // 回收已结束的任务
+            running.retain(|id| {
+                if Executor::is_running(*id) {
+                    return true;
+                }
+                Executor::release(*id);
+                false
+            });
+
+            // 同一分钟只执行一次, RTC 被向前调整时不补执行错过的时间
+            let minute = now.and_utc().timestamp().div_euclid(60);
+            if last_minute != Some(minute) {
+                last_minute = Some(minute);
+                for job in Cron::get_mut().jobs.iter() {
+                    if job.expr.matches(&now) {
+                        // 保留退出状态直到被回收, 期间任务ID不会被复用
+                        if let Some(id) = Self::run_job(job) {
+                            Executor::hold(id);
+                            running.push(id);
+                        }
+                    }
+                }
+            }
+
+            // 睡眠到下一分钟开始
+            let remain_ms = (60 - now.second().min(59)) * 1000 - now.nanosecond() / 1_000_000 % 1000;
+            sys::sleep_ms(remain_ms.max(RTC_RETRY_MS)).await;
+        }
+    }
+}
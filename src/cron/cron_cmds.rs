@@ -0,0 +1,125 @@
+use crate::console::{quote_word, CmdParser};
+use crate::cron::Cron;
+use crate::executor::ExitCode;
+use crate::println;
+use alloc::{boxed::Box, string::{String, ToString}, vec::Vec};
+use async_trait::async_trait;
+
+#[allow(unused)]
+#[derive(Default)]
+pub struct CronCmds;
+
+#[allow(unused)]
+impl CronCmds {
+    pub fn new() -> Self {
+        CronCmds
+    }
+
+    fn cmd_list(&self) -> ExitCode {
+        println!("id\tschedule\t\ttask");
+        for job in Cron::jobs().iter() {
+            let mark = if job.persistent { "" } else { " (runnable)" };
+            println!("{}\t{:<16}\t{}{}", job.id, job.spec, job.task, mark);
+        }
+        0
+    }
+
+    fn cmd_add(&self, args: &[String]) -> ExitCode {
+        let fields = match args.get(2) {
+            Some(arg) if arg.starts_with('@') => 1,
+            _ => 5,
+        };
+        if args.len() <= 2 + fields {
+            println!("Usage: crontab add <min> <hour> <day> <month> <weekday> <cmd>");
+            return 2;
+        }
+        let spec = args[2..2 + fields].join(" ");
+        // 参数已被控制台去掉引号并展开变量, 重新加引号以便执行时还原为相同的参数
+        let cmdline = args[2 + fields..]
+            .iter()
+            .map(|arg| quote_word(arg))
+            .collect::<Vec<_>>()
+            .join(" ");
+        match Cron::add_command(&spec, &cmdline) {
+            Ok(id) => {
+                println!("Added job {}", id);
+                0
+            }
+            Err(e) => {
+                println!("Error adding job: {}", e);
+                1
+            }
+        }
+    }
+
+    fn cmd_remove(&self, args: &[String]) -> ExitCode {
+        if let Some(id_str) = args.get(2) {
+            if let Ok(id) = id_str.parse::<u32>() {
+                match Cron::remove(id) {
+                    Ok(true) => 0,
+                    Ok(false) => {
+                        println!("No such job: {}", id);
+                        1
+                    }
+                    Err(e) => {
+                        println!("Error saving crontab: {}", e);
+                        1
+                    }
+                }
+            } else {
+                println!("Invalid job ID: {}", id_str);
+                1
+            }
+        } else {
+            println!("Usage: crontab remove <id>");
+            2
+        }
+    }
+
+    fn cmd_crontab(&self, args: &[String]) -> ExitCode {
+        match args.get(1).map(|s| s.as_str()) {
+            None | Some("list") | Some("-l") => self.cmd_list(),
+            Some("add") => self.cmd_add(args),
+            Some("remove") | Some("rm") => self.cmd_remove(args),
+            Some(sub) => {
+                println!("Unknown crontab command: {}", sub);
+                2
+            }
+        }
+    }
+}
+
+#[async_trait(?Send)]
+impl CmdParser for CronCmds {
+    fn help(&self) -> &'static [(&'static str, &'static str)] {
+        &[
+            ("crontab [list]", "List scheduled jobs"),
+            (
+                "crontab add <min> <hour> <day> <month> <weekday> <cmd>",
+                "Schedule a command, @hourly/@daily/@weekly/... also accepted",
+            ),
+            ("crontab remove <id>", "Remove a scheduled job"),
+        ]
+    }
+
+    fn complete(&self, args: &[String]) -> Option<Vec<String>> {
+        match (args[0].as_str(), args.len()) {
+            ("crontab", 2) => Some(["list", "add", "remove"].iter().map(|s| s.to_string()).collect()),
+            ("crontab", _) if args[1] == "remove" || args[1] == "rm" => {
+                Some(Cron::jobs().iter().map(|job| job.id.to_string()).collect())
+            }
+            _ => None,
+        }
+    }
+
+    async fn parse(&self, args: &Vec<String>) -> ExitCode {
+        if let Some(cmd) = args.first() {
+            match cmd.as_str() {
+                "crontab" => self.cmd_crontab(args),
+                _ => return 127, // Command not found
+            }
+        } else {
+            127 // No command provided
+        }
+    }
+}
@@ -0,0 +1,245 @@
+use anyhow::{anyhow, Result};
+use chrono::{Datelike, NaiveDateTime, Timelike};
+
+const MONTH_NAMES: [&str; 12] = [
+    "jan", "feb", "mar", "apr", "may", "jun", "jul", "aug", "sep", "oct", "nov", "dec",
+];
+const WEEKDAY_NAMES: [&str; 7] = ["sun", "mon", "tue", "wed", "thu", "fri", "sat"];
+
+/// cron 表达式: 分 时 日 月 周, 例如 "0 2 * * *" 表示每天 02:00
+///
+/// 每个字段支持 `*`, `5`, `1-5`, `*/15`, `0-30/10` 及逗号分隔的列表,
+/// 月和周可使用英文缩写 (jan, mon), 周日为 0 或 7;
+/// 也支持 @yearly, @monthly, @weekly, @daily, @hourly
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct CronExpr {
+    minutes: u64,  // 位 0-59
+    hours: u32,    // 位 0-23
+    days: u32,     // 位 1-31
+    months: u16,   // 位 1-12
+    weekdays: u8,  // 位 0-6, 周日为 0
+    any_day: bool, // 日为 *
+    any_weekday: bool, // 周为 *
+}
+
+impl CronExpr {
+    pub fn parse(expr: &str) -> Result<Self> {
+        let expr = match expr.trim() {
+            "@yearly" | "@annually" => "0 0 1 1 *",
+            "@monthly" => "0 0 1 * *",
+            "@weekly" => "0 0 * * 0",
+            "@daily" | "@midnight" => "0 0 * * *",
+            "@hourly" => "0 * * * *",
+            expr => expr,
+        };
+        let fields: [&str; 5] = match expr.split_whitespace().collect::<alloc::vec::Vec<_>>().try_into() {
+            Ok(fields) => fields,
+            Err(_) => return Err(anyhow!("cron expression needs 5 fields: {}", expr)),
+        };
+        let minutes = parse_field(fields[0], 0, 59, &[])?;
+        let hours = parse_field(fields[1], 0, 23, &[])?;
+        let days = parse_field(fields[2], 1, 31, &[])?;
+        let months = parse_field(fields[3], 1, 12, &MONTH_NAMES)?;
+        let mut weekdays = parse_field(fields[4], 0, 7, &WEEKDAY_NAMES)?;
+        // 7 也表示周日
+        if weekdays & (1 << 7) != 0 {
+            weekdays = (weekdays & !(1 << 7)) | 1;
+        }
+        Ok(CronExpr {
+            minutes,
+            hours: hours as u32,
+            days: days as u32,
+            months: months as u16,
+            weekdays: weekdays as u8,
+            any_day: fields[2] == "*",
+            any_weekday: fields[4] == "*",
+        })
+    }
+
+    /// 判断时间是否匹配, 只比较到分钟
+    ///
+    /// 与标准 cron 相同, 日和周都不为 * 时满足其中之一即可
+    pub fn matches(&self, dt: &NaiveDateTime) -> bool {
+        let day_match = self.days & (1 << dt.day()) != 0;
+        let weekday_match = self.weekdays & (1 << dt.weekday().num_days_from_sunday()) != 0;
+        let date_match = match (self.any_day, self.any_weekday) {
+            (false, false) => day_match || weekday_match,
+            _ => day_match && weekday_match,
+        };
+        self.minutes & (1 << dt.minute()) != 0
+            && self.hours & (1 << dt.hour()) != 0
+            && self.months & (1 << dt.month()) != 0
+            && date_match
+    }
+}
+
+impl core::str::FromStr for CronExpr {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        Self::parse(s)
+    }
+}
+
+// 解析一个字段, 返回匹配值的位图
+fn parse_field(field: &str, min: u32, max: u32, names: &[&str]) -> Result<u64> {
+    let mut bits = 0u64;
+    for part in field.split(',') {
+        let (range, step) = match part.split_once('/') {
+            Some((range, step)) => match step.parse::<u32>() {
+                Ok(step) if step > 0 => (range, step),
+                _ => return Err(anyhow!("invalid cron step: {}", part)),
+            },
+            None => (part, 1),
+        };
+        let (start, end) = if range == "*" {
+            (min, max)
+        } else if let Some((start, end)) = range.split_once('-') {
+            (parse_value(start, min, names)?, parse_value(end, min, names)?)
+        } else {
+            let value = parse_value(range, min, names)?;
+            // "5/10" 表示从 5 开始到最大值
+            if part.contains('/') {
+                (value, max)
+            } else {
+                (value, value)
+            }
+        };
+        if start < min || end > max || start > end {
+            return Err(anyhow!("cron field out of range: {}", part));
+        }
+        for value in (start..=end).step_by(step as usize) {
+            bits |= 1 << value;
+        }
+    }
+    Ok(bits)
+}
+
+fn parse_value(value: &str, min: u32, names: &[&str]) -> Result<u32> {
+    if let Some(index) = names.iter().position(|name| name.eq_ignore_ascii_case(value)) {
+        // 月从 1 开始, 周从 0 开始
+        return Ok(index as u32 + min);
+    }
+    value
+        .parse::<u32>()
+        .map_err(|_| anyhow!("invalid cron value: {}", value))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::NaiveDate;
+
+    fn at(year: i32, month: u32, day: u32, hour: u32, minute: u32) -> NaiveDateTime {
+        NaiveDate::from_ymd_opt(year, month, day)
+            .unwrap()
+            .and_hms_opt(hour, minute, 0)
+            .unwrap()
+    }
+
+    fn matches(expr: &str, dt: NaiveDateTime) -> bool {
+        CronExpr::parse(expr).unwrap().matches(&dt)
+    }
+
+    #[test]
+    fn fixed_time() {
+        // 2024-01-01 是周一
+        assert!(matches("0 2 * * *", at(2024, 1, 1, 2, 0)));
+        assert!(!matches("0 2 * * *", at(2024, 1, 1, 2, 1)));
+        assert!(!matches("0 2 * * *", at(2024, 1, 1, 3, 0)));
+        assert!(matches("* * * * *", at(2024, 2, 29, 23, 59)));
+    }
+
+    #[test]
+    fn ranges_steps_lists() {
+        let expr = "*/15 8-17/3 * * *";
+        assert!(matches(expr, at(2024, 1, 1, 8, 0)));
+        assert!(matches(expr, at(2024, 1, 1, 11, 45)));
+        assert!(matches(expr, at(2024, 1, 1, 17, 30)));
+        assert!(!matches(expr, at(2024, 1, 1, 9, 0)));
+        assert!(!matches(expr, at(2024, 1, 1, 8, 10)));
+        // "5/20" 从 5 开始到最大值
+        let expr = "5/20 0 * * *";
+        assert!(matches(expr, at(2024, 1, 1, 0, 5)));
+        assert!(matches(expr, at(2024, 1, 1, 0, 45)));
+        assert!(!matches(expr, at(2024, 1, 1, 0, 0)));
+        let expr = "0,30 1,13 1,15 * *";
+        assert!(matches(expr, at(2024, 1, 15, 13, 30)));
+        assert!(!matches(expr, at(2024, 1, 16, 13, 30)));
+    }
+
+    #[test]
+    fn names() {
+        let expr = "0 0 * jan,jul mon-fri";
+        assert!(matches(expr, at(2024, 1, 1, 0, 0))); // 周一
+        assert!(matches(expr, at(2024, 7, 5, 0, 0))); // 周五
+        assert!(!matches(expr, at(2024, 1, 6, 0, 0))); // 周六
+        assert!(!matches(expr, at(2024, 2, 1, 0, 0))); // 二月
+        assert_eq!(
+            CronExpr::parse("0 0 * JAN MON").unwrap(),
+            CronExpr::parse("0 0 * 1 1").unwrap()
+        );
+    }
+
+    #[test]
+    fn sunday_is_0_or_7() {
+        // 2024-01-07 是周日
+        assert!(matches("0 0 * * 0", at(2024, 1, 7, 0, 0)));
+        assert!(matches("0 0 * * 7", at(2024, 1, 7, 0, 0)));
+        assert!(matches("0 0 * * sun", at(2024, 1, 7, 0, 0)));
+        assert!(matches("0 0 * * 5-7", at(2024, 1, 7, 0, 0)));
+        assert!(!matches("0 0 * * 7", at(2024, 1, 6, 0, 0)));
+        assert_eq!(CronExpr::parse("0 0 * * 7").unwrap(), CronExpr::parse("0 0 * * 0").unwrap());
+    }
+
+    #[test]
+    fn day_and_weekday() {
+        // 日和周都不为 * 时满足其一即可: 每月 13 日或每个周五
+        let expr = "0 0 13 * fri";
+        assert!(matches(expr, at(2024, 1, 13, 0, 0))); // 周六, 13 日
+        assert!(matches(expr, at(2024, 1, 5, 0, 0))); // 周五, 5 日
+        assert!(!matches(expr, at(2024, 1, 6, 0, 0)));
+        // 只限定其中之一时按该字段匹配
+        assert!(matches("0 0 13 * *", at(2024, 1, 13, 0, 0)));
+        assert!(!matches("0 0 13 * *", at(2024, 1, 5, 0, 0)));
+        assert!(matches("0 0 * * fri", at(2024, 1, 5, 0, 0)));
+        assert!(!matches("0 0 * * fri", at(2024, 1, 13, 0, 0)));
+    }
+
+    #[test]
+    fn shortcuts() {
+        assert_eq!(CronExpr::parse("@daily").unwrap(), CronExpr::parse("0 0 * * *").unwrap());
+        assert_eq!(CronExpr::parse("@midnight").unwrap(), CronExpr::parse("@daily").unwrap());
+        assert!(matches("@hourly", at(2024, 1, 1, 5, 0)));
+        assert!(!matches("@hourly", at(2024, 1, 1, 5, 1)));
+        assert!(matches("@weekly", at(2024, 1, 7, 0, 0)));
+        assert!(!matches("@weekly", at(2024, 1, 8, 0, 0)));
+        assert!(matches("@monthly", at(2024, 3, 1, 0, 0)));
+        assert!(matches("@yearly", at(2025, 1, 1, 0, 0)));
+        assert!(!matches("@yearly", at(2025, 2, 1, 0, 0)));
+    }
+
+    #[test]
+    fn invalid() {
+        for expr in [
+            "",
+            "* * * *",
+            "* * * * * *",
+            "60 * * * *",
+            "* 24 * * *",
+            "* * 0 * *",
+            "* * 32 * *",
+            "* * * 13 *",
+            "* * * * 8",
+            "*/0 * * * *",
+            "*/x * * * *",
+            "5-1 * * * *",
+            "a * * * *",
+            "* * * foo *",
+            "1,,2 * * * *",
+            "@often",
+        ] {
+            assert!(CronExpr::parse(expr).is_err(), "{:?} should be rejected", expr);
+        }
+    }
+}
@@ -0,0 +1,8 @@
+#[allow(clippy::module_inception)]
+mod cron;
+mod cron_cmds;
+mod cron_expr;
+
+pub use cron::*;
+pub use cron_cmds::*;
+pub use cron_expr::*;
@@ -8,4 +8,30 @@ pub trait CpuDriver {
         println!("Panic: {}", panic_info);
         loop {}
     }
+
+    /// 执行器没有就绪任务时调用, 可在此执行 WFI 或进入低功耗模式
+    ///
+    /// `max_sleep_ms` 为距离下一个定时器到期的毫秒数, None 表示没有定时器.
+    /// 调用时处于临界区内, 挂起的中断仍应能唤醒 CPU (如 Cortex-M 的 WFI),
+    /// 中断发生或休眠时长到达后必须返回; 若休眠期间系统节拍停止,
+    /// 实现需在返回前补偿 `SysTickDriver::get_system_ms` 的计数
+    fn cpu_idle(&mut self, _max_sleep_ms: Option<u32>) {}
+
+    /// 进入临界区 (例如屏蔽中断), 返回进入前的状态, 用于支持嵌套
+    ///
+    /// **必须实现**: 执行器的唤醒队列, `AtomicWaker` 等会被中断访问
+    /// (例如 `UartDriver::rx_complete` 唤醒任务), 只靠临界区保护;
+    /// 空实现会导致中断与任务之间的数据竞争. 单核 MCU 通常保存 PRIMASK 后关中断,
+    /// 在主机上模拟时需用锁保证中断线程与执行器互斥
+    fn cpu_enter_critical(&mut self) -> usize;
+
+    /// 退出临界区, 恢复 `cpu_enter_critical` 返回的状态
+    fn cpu_exit_critical(&mut self, state: usize);
+
+    /// 高精度微秒计数 (允许回绕), 例如由 DWT 周期计数换算, 用于任务耗时统计
+    ///
+    /// 返回 None 时使用系统毫秒计数
+    fn cpu_get_time_us(&mut self) -> Option<u32> {
+        None
+    }
 }
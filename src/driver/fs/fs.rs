@@ -2,6 +2,9 @@ use crate::{driver::Driver, println, singleton};
 use alloc::{boxed::Box, format, string::String, string::ToString, vec::Vec};
 use anyhow::{anyhow, Result};
 use core::any::Any;
+use core::task::{Context, Poll};
+
+use crate::sys::{InputStream, OutputStream};
 
 pub trait FsHandle: Driver + Any {
     #[allow(unused)]
@@ -119,6 +122,24 @@ impl Drop for File {
     }
 }
 
+/// 文件可作为任务的标准输出, 用于输出重定向
+impl OutputStream for File {
+    fn write(&mut self, data: &[u8]) {
+        let _ = File::write(self, data);
+    }
+
+    fn flush(&mut self) {
+        let _ = File::flush(self);
+    }
+}
+
+/// 文件可作为任务的标准输入, 用于输入重定向, 读取出错时视为结尾
+impl InputStream for File {
+    fn poll_read(&mut self, _cx: &mut Context<'_>, buf: &mut [u8]) -> Poll<usize> {
+        Poll::Ready(self.read(buf).unwrap_or(0))
+    }
+}
+
 pub trait DirEntry: Any {
     #[allow(unused)]
     fn as_any(&self) -> &dyn Any;
@@ -1,8 +1,8 @@
 use crate::console::CmdParser;
 use crate::driver::fs::{File, Fs};
 use crate::executor::{ ExitCode};
-use crate::{print, println};
-use alloc::{boxed::Box, string::String, vec::Vec};
+use crate::{println, sys};
+use alloc::{boxed::Box, string::{String, ToString}, vec::Vec};
 use async_trait::async_trait;
 
 #[allow(unused)]
@@ -232,7 +232,7 @@ impl FsCmds {
             2
         }
     }
-    fn cmd_cat(&self, args: &Vec<String>) -> ExitCode {
+    async fn cmd_cat(&self, args: &Vec<String>) -> ExitCode {
         if let Some(path) = args.get(1) {
             let path = Fs::to_absolute_path(path);
             match crate::driver::fs::File::open(&path, "r") {
@@ -244,7 +244,9 @@ impl FsCmds {
                             Ok(n) => {
                                 let content =
                                     core::str::from_utf8(&buffer[..n]).unwrap_or("[Invalid UTF-8]");
-                                print!("{}", content);
+                                // 逐块写入并让出执行权, 管道满时等待读端取走数据
+                                sys::stdout_write_all(content.as_bytes()).await;
+                                sys::yield_now().await;
                             }
                             Err(e) => {
                                 println!("Error reading file {}: {}", path, e);
@@ -267,8 +269,86 @@ impl FsCmds {
                 }
             }
         } else {
-            println!("Usage: cat <path>");
-            2
+            // 未指定文件时输出标准输入的内容
+            let mut buffer = [0u8; 256];
+            loop {
+                match sys::stdin_read(&mut buffer).await {
+                    0 => break,
+                    n => sys::stdout_write_all(&buffer[..n]).await,
+                }
+            }
+            sys::stdout_flush();
+            0
+        }
+    }
+
+    // 输出包含 pattern 的行, 返回是否匹配
+    fn grep_line(line: &[u8], pattern: &str) -> bool {
+        let text = String::from_utf8_lossy(line);
+        let text = text.trim_end_matches('\r');
+        if text.contains(pattern) {
+            println!("{}", text);
+            true
+        } else {
+            false
+        }
+    }
+
+    async fn cmd_grep(&self, args: &[String]) -> ExitCode {
+        let pattern = match args.get(1) {
+            Some(pattern) => pattern,
+            None => {
+                println!("Usage: grep <pattern> [path]");
+                return 2;
+            }
+        };
+        // 未指定文件时从标准输入读取
+        let mut file = match args.get(2) {
+            Some(path) => {
+                let path = Fs::to_absolute_path(path);
+                match File::open(&path, "r") {
+                    Ok(file) => Some(file),
+                    Err(e) => {
+                        println!("Error opening file {}: {}", path, e);
+                        return 2;
+                    }
+                }
+            }
+            None => None,
+        };
+        let mut matched = false;
+        let mut line = Vec::new();
+        let mut buffer = [0u8; 128];
+        loop {
+            let n = match file.as_mut() {
+                Some(file) => match file.read(&mut buffer) {
+                    Ok(n) => n,
+                    Err(e) => {
+                        println!("Error reading file: {}", e);
+                        return 2;
+                    }
+                },
+                None => sys::stdin_read(&mut buffer).await,
+            };
+            if n == 0 {
+                break;
+            }
+            for &byte in buffer[..n].iter() {
+                if byte == b'\n' {
+                    matched |= Self::grep_line(&line, pattern);
+                    line.clear();
+                } else {
+                    line.push(byte);
+                }
+            }
+        }
+        if !line.is_empty() {
+            matched |= Self::grep_line(&line, pattern);
+        }
+        if matched {
+            0
+        } else {
+            1
         }
     }
 
@@ -327,11 +407,22 @@ impl CmdParser for FsCmds {
             ("cd <path>", "Change current directory to path"),
             ("pwd", "Print current working directory"),
             ("touch <path>", "Create an empty file at path"),
-            ("cat <path>", "Display the contents of the file at path"),
+            ("cat [path]", "Display the contents of the file at path, or of stdin"),
+            ("grep <pattern> [path]", "Print lines of the file or stdin containing pattern"),
             ("write <path> <content>", "Write content to the file at path"),
         ]
     }
 
+    fn complete(&self, args: &[String]) -> Option<Vec<String>> {
+        match args[0].as_str() {
+            // 补全挂载点
+            "mount" | "unmount" | "format" | "info" => {
+                Some(Fs::fstab().iter().map(|entry| entry.mount_point.to_string()).collect())
+            }
+            _ => None,
+        }
+    }
+
     async fn parse(&self, args: &Vec<String>) -> ExitCode {
         if let Some(cmd) = args.get(0) {
             match cmd.as_str() {
@@ -348,7 +439,8 @@ impl CmdParser for FsCmds {
                 "cd" => self.cmd_cd(args),
                 "pwd" => self.cmd_pwd(args),
                 "touch" => self.cmd_touch(args),
-                "cat" => self.cmd_cat(args),
+                "cat" => self.cmd_cat(args).await,
+                "grep" => self.cmd_grep(args).await,
                 "write" => self.cmd_write(args),
                 _ => return 127, // Command not found
             }
@@ -19,5 +19,6 @@ pub mod systick;
 pub mod uart;
 pub mod cpu;
 pub mod tty;
+pub mod watchdog;
 
 pub use crate::lazy_init;
@@ -6,10 +6,17 @@ pub trait TtyDriver: Driver {
     fn tty_flush(&mut self);
     fn tty_get_break(&mut self) -> bool;
 
+    /// 是否收到挂起请求 (Ctrl+Z), 读取后清除, 不支持作业控制的终端可不实现
+    #[allow(unused)]
+    fn tty_get_suspend(&mut self) -> bool {
+        false
+    }
+
     #[allow(unused)]
     fn tty_clear_rx(&mut self) {
         while self.tty_getc().is_some() {}
         while self.tty_get_break() {}
+        while self.tty_get_suspend() {}
     }
     #[allow(unused)]
     fn tty_read(&mut self, buffer: &mut [u8]) -> usize {
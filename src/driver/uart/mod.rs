@@ -1,10 +1,24 @@
-use crate::{driver::tty::TtyDriver, driver::Driver, sys, util::RingBuf};
+use crate::executor::TimerHandle;
+use crate::sys::{self, AtomicWaker, Duration, Instant};
+use crate::util::{RingBuf, SpscRingBuf};
+use crate::{driver::tty::TtyDriver, driver::Driver};
 use anyhow::Result;
-
+use core::future::poll_fn;
+use core::task::Poll;
+
+/// 串口驱动
+///
+/// 接口变更 (不兼容): `rx` 由 `fn rx(&mut self) -> &mut RingBuf<u8, RX_SIZE>` 改为
+/// `fn rx(&self) -> &SpscRingBuf<u8, RX_SIZE>`, 新增必须实现的 `rx_waker`,
+/// `rx_complete` 改为接收 `&self`. 现有实现需改为持有 `SpscRingBuf` 和 `AtomicWaker`,
+/// 并在接收中断中调用 `rx_complete`. 中断会唤醒任务, 板级的 `CpuDriver` 必须实现临界区
 pub trait UartDriver<const RX_SIZE: usize = 512, const TX_SIZE: usize = RX_SIZE>:
     Driver + TtyDriver
 {
-    fn rx(&mut self) -> &mut RingBuf<u8, RX_SIZE>;
+    /// 接收缓冲区, 由中断写入, 任务读取
+    fn rx(&self) -> &SpscRingBuf<u8, RX_SIZE>;
+    /// 等待接收数据的任务的唤醒器, 中断收到数据后唤醒
+    fn rx_waker(&self) -> &AtomicWaker;
     fn tx(&mut self) -> &mut RingBuf<u8, TX_SIZE>;
     fn uart_write(&mut self, data: &[u8]);
 
@@ -41,9 +55,10 @@ pub trait UartDriver<const RX_SIZE: usize = 512, const TX_SIZE: usize = RX_SIZE>
         self.uart_write(&buffer[..count]);
     }
 
-    /// 用于中断中调用来标记接收到字节, 并且将下一个接收字节返回
-    fn rx_complete(&mut self, byte: u8) {
+    /// 用于中断中调用来标记接收到字节, 并唤醒等待接收的任务
+    fn rx_complete(&self, byte: u8) {
         self.rx().push(byte);
+        self.rx_waker().wake();
     }
 
     fn read(&mut self, buffer: &mut [u8]) -> usize {
@@ -61,18 +76,15 @@ pub trait UartDriver<const RX_SIZE: usize = 512, const TX_SIZE: usize = RX_SIZE>
 
     fn getc(&mut self, timeout_ms: u32) -> impl crate::core::future::Future<Output = Option<u8>> {
         async move {
-            let start_ms = sys::get_system_ms();
-            loop {
-                if let Some(b) = self.read_byte() {
-                    return Some(b);
-                }
-
-                if sys::get_system_ms().wrapping_sub(start_ms) >= timeout_ms {
-                    return None;
+            let byte = poll_fn(|cx| {
+                // 先登记唤醒器再检查, 避免中断在两者之间到达时丢失唤醒
+                self.rx_waker().register(cx.waker());
+                match self.read_byte() {
+                    Some(b) => Poll::Ready(b),
+                    None => Poll::Pending,
                 }
-
-                sys::sleep_ms(1).await;
-            }
+            });
+            sys::timeout(timeout_ms, byte).await.ok()
         }
     }
 
@@ -84,26 +96,31 @@ pub trait UartDriver<const RX_SIZE: usize = 512, const TX_SIZE: usize = RX_SIZE>
     ) -> impl crate::core::future::Future<Output = usize> {
         async move {
             let mut count = 0;
-            let mut last_byte_ms = sys::get_system_ms();
+            let byte_interval = Duration::from_millis(byte_interval_ms as u64);
+            let mut last_byte = Instant::now();
+            let mut timer = TimerHandle::new(); // 提前丢弃时注销定时器
 
-            loop {
-                if let Some(b) = self.read_byte() {
+            poll_fn(|cx| {
+                self.rx_waker().register(cx.waker());
+                while let Some(b) = self.read_byte() {
                     if count < buffer.len() {
                         buffer[count] = b;
                         count += 1;
                     }
-                    last_byte_ms = sys::get_system_ms();
-                } else {
-                    // 检查是否超时
-                    if count > 0
-                        && sys::get_system_ms().wrapping_sub(last_byte_ms) >= byte_interval_ms
-                    {
-                        break;
-                    }
+                    last_byte = Instant::now();
                 }
 
-                sys::sleep_ms(1).await;
-            }
+                // 收到第一个字节后才开始计算帧间隔
+                if count > 0 {
+                    let frame_end = last_byte + byte_interval;
+                    if Instant::now() >= frame_end {
+                        return Poll::Ready(());
+                    }
+                    timer.register(frame_end, cx.waker());
+                }
+                Poll::Pending
+            })
+            .await;
 
             count
         }
@@ -0,0 +1,13 @@
+use crate::driver::Driver;
+
+/// 硬件看门狗, 超时未喂狗时复位系统
+///
+/// 由 `driver_init` 启动看门狗, 执行器在调度循环中调用 `wdg_feed`;
+/// 有任务通过 `Executor::watchdog_register` 注册时, 只在所有注册任务按时签到时喂狗
+pub trait WatchdogDriver: Driver {
+    /// 喂狗, 重新开始超时计时
+    fn wdg_feed(&mut self);
+
+    /// 看门狗超时时间(毫秒), 执行器空闲休眠不会超过该时间的一半
+    fn wdg_get_timeout_ms(&self) -> u32;
+}
@@ -1,14 +1,21 @@
+use crate::executor::join_handle::{self, JoinHandle};
+use crate::executor::supervisor::Supervisor;
+use crate::executor::timer::{SoftTimerCallback, SoftTimers, TimerQueue};
 use crate::executor::Runnable;
 use crate::util::RingBuf;
+use crate::sys::{CancellationToken, Instant, SimpleOs, Stdin, Stdout};
 use crate::{println, singleton, sys};
 use alloc::boxed::Box;
 use alloc::collections::VecDeque;
 use alloc::string::String;
 use alloc::vec::Vec;
+use core::any::Any;
 use core::future::Future;
 use core::option::Option;
 use core::pin::Pin;
 use core::task::{Context, Poll, RawWaker, RawWakerVTable, Waker};
+use core::time::Duration;
+use core::future::poll_fn;
 
 pub type TaskId = u16;
 pub type TaskCountType = TaskId;
@@ -20,7 +27,6 @@ pub enum ExitStatus {
     ErrorPid,         // 等待了一个无效的PID
     NotExist,         // 任务不存在
     NotRunning,       // 当前任务未运行, 必须在任务上下文中调用
-    Aborted,          // 任务异常中止, 不应该发生
 }
 
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
@@ -48,42 +54,174 @@ pub enum SignalAction {
     Continue,            // 继续执行
 }
 
+/// 任务优先级, 高优先级的就绪任务总是先于低优先级任务被轮询
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Priority {
+    Low = 0,      // 后台任务, 例如文件拷贝
+    #[default]
+    Normal = 1,   // 默认优先级
+    High = 2,     // 需要及时响应的任务, 例如通信协议
+    Critical = 3, // 最高优先级
+}
+
+pub const PRIORITY_LEVELS: usize = 4;
+
+/// 任务运行统计
+#[derive(Clone, Copy, Debug, Default)]
+pub struct TaskStats {
+    pub poll_count: u32,    // 轮询次数
+    pub total_poll_us: u64, // 累计轮询耗时(微秒)
+    pub max_poll_us: u32,   // 单次轮询最长耗时(微秒)
+    pub spawn_ms: u64,      // 创建时间(系统毫秒)
+    pub last_run_ms: u64,   // 最近一次轮询时间(系统毫秒)
+    pub long_polls: u32,    // 轮询耗时超过阈值的次数
+}
+
+/// 任务信息快照, 用于 ps 等命令显示
+#[derive(Clone, Debug)]
+pub struct TaskInfo {
+    pub id: TaskId,
+    pub parent: Option<TaskId>,
+    pub pgid: TaskId,
+    pub cmd: String,
+    pub priority: Priority,
+    pub paused: bool,
+    pub stats: TaskStats,
+    pub exit_code: Option<ExitCode>, // 已结束未回收的任务(僵尸)的退出码
+}
+
 pub struct Task {
     id: TaskId,
+    parent: Option<TaskId>, // 父任务, 父任务结束后由祖父任务接管
+    pgid: TaskId,           // 任务组ID, 默认继承父任务
     cmd: String,
-    future: Pin<Box<dyn Future<Output = ExitCode>>>,
+    priority: Priority,
+    future: Option<Pin<Box<dyn Future<Output = ExitCode>>>>,     // 轮询期间被取出, 结束后置空
     paused: bool,                                                // 任务是否被暂停
+    scheduled: bool,                                             // 是否已在就绪队列中
     exited: Option<ExitCode>,                                    // 任务结束状态
     waiters: TaskCountType,                                      // 等待该任务完成的任务数量
+    exit_wakers: Vec<Waker>,                                     // 任务结束时需要唤醒的等待者
+    child_waker: Option<Waker>,                                  // wait_any 等待子任务结束
     pending_signals: RingBuf<Signal, 4>,                         // 待处理的信号队列
     signal_handler: Option<Box<dyn Fn(Signal) -> SignalAction>>, // 信号处理器
+    locals: Vec<(usize, Box<dyn Any>)>,                          // 任务局部变量
+    stats: TaskStats,                                            // 运行统计
+    cancel_token: Option<CancellationToken>,                     // 取消令牌, 任务首次获取时创建
+    terminating: Option<ExitCode>,                               // 正在协作式终止, 结束时的退出码
+    kill_deadline: Option<Instant>,                              // 到期仍未结束时强制终止
+    cleanups: Vec<Box<dyn FnOnce()>>,                            // 任务结束时按注册的逆序执行
+    stdin: Option<Stdin>,                                        // 标准输入, None 为终端, 默认继承父任务
+    stdout: Option<Stdout>,                                      // 标准输出, None 为终端, 默认继承父任务
 }
 
 impl Task {
+    fn info(&self) -> TaskInfo {
+        TaskInfo {
+            id: self.id,
+            parent: self.parent,
+            pgid: self.pgid,
+            cmd: self.cmd.clone(),
+            priority: self.priority,
+            paused: self.paused,
+            stats: self.stats,
+            exit_code: self.exited,
+        }
+    }
+
     pub fn new(id: TaskId, cmd: String, future: Pin<Box<dyn Future<Output = ExitCode>>>) -> Self {
         Self {
             id,
+            parent: None,
+            pgid: id,
             cmd,
-            future,
+            priority: Priority::default(),
+            future: Some(future),
             paused: false,
+            scheduled: false,
             exited: None,
             waiters: 0,
+            exit_wakers: Vec::new(),
+            child_waker: None,
             pending_signals: RingBuf::new(),
             signal_handler: None,
+            locals: Vec::new(),
+            stats: TaskStats {
+                spawn_ms: system_ms64(),
+                ..TaskStats::default()
+            },
+            cancel_token: None,
+            terminating: None,
+            kill_deadline: None,
+            cleanups: Vec::new(),
+            stdin: None,
+            stdout: None,
         }
     }
 }
 
+const WAKE_QUEUE_SIZE: usize = 64; // 待处理唤醒队列长度
+const DEFAULT_LONG_POLL_THRESHOLD_US: u32 = 100_000; // 默认长时间轮询阈值
+const DEFAULT_CANCEL_GRACE_MS: u32 = 1000; // 默认协作式终止宽限期
+const KILL_EXIT_CODE: ExitCode = -9; // SIGKILL 强制终止的退出码
+const ZOMBIE_LIMIT: usize = 16; // 保留的僵尸任务数量上限, 超出时丢弃最早的无人等待的僵尸
+
+// 已结束但未被回收的任务, 保留退出状态
+struct Zombie {
+    info: TaskInfo,
+    waiters: TaskCountType, // 仍在等待该任务的任务数量, 不为0时不会被丢弃
+}
+
+impl Zombie {
+    fn exit_code(&self) -> ExitCode {
+        self.info.exit_code.unwrap_or_default()
+    }
+}
+
+/// 长时间轮询处理函数, 参数为任务ID, 任务名和本次轮询耗时(微秒)
+///
+/// 返回 false 时执行器停止喂狗, 由硬件看门狗复位系统
+pub type LongPollHandler = Box<dyn FnMut(TaskId, &str, u32) -> bool>;
+
+// 待处理的唤醒, 唤醒可能来自中断, 只在临界区内访问, 不分配内存
+struct WakeQueue {
+    ids: RingBuf<TaskId, WAKE_QUEUE_SIZE>,
+    overflow: bool, // 队列溢出, 需要唤醒全部任务
+}
+
+singleton!(WakeQueue {
+    ids: RingBuf::new(),
+    overflow: false,
+});
+
 pub struct Executor {
     tasks: VecDeque<Task>,
+    zombies: VecDeque<Zombie>, // 已结束未回收的任务, 按结束顺序排列
+    ready: [VecDeque<TaskId>; PRIORITY_LEVELS], // 按优先级划分的就绪队列, 只有被唤醒的任务才会被轮询
+    timers: TimerQueue, // 定时器服务, 保存睡眠任务的到期时间
+    soft_timers: SoftTimers, // 软件定时器, 到期时在执行器上下文中调用回调
+    supervisor: Supervisor, // 任务存活监控, 全部存活时才喂硬件看门狗
     next_id_hint: TaskId,
     current_task_id: Option<TaskId>,
+    long_poll_threshold_us: u32, // 轮询耗时超过该值视为阻塞执行器, 0 表示不检测
+    long_poll_handler: Option<LongPollHandler>, // 为 None 时打印警告并继续喂狗
+    watchdog_starved: bool, // 已停止喂狗, 等待硬件看门狗复位
+    cancel_grace_ms: u32, // 协作式终止的宽限期, 到期后强制终止
 }
 
 singleton!(Executor {
     tasks: VecDeque::new(),
+    zombies: VecDeque::new(),
+    ready: [const { VecDeque::new() }; PRIORITY_LEVELS],
+    timers: TimerQueue::new(),
+    soft_timers: SoftTimers::new(),
+    supervisor: Supervisor::new(),
     next_id_hint: 0,
     current_task_id: None,
+    long_poll_threshold_us: DEFAULT_LONG_POLL_THRESHOLD_US,
+    long_poll_handler: None,
+    watchdog_starved: false,
+    cancel_grace_ms: DEFAULT_CANCEL_GRACE_MS,
 });
 
 impl Executor {
@@ -93,8 +231,10 @@ impl Executor {
         let start = candidate;
 
         loop {
-            // 检查当前候选ID是否可用
-            if !self.tasks.iter().any(|task| task.id == candidate) {
+            // 检查当前候选ID是否可用, 未回收的僵尸任务仍占用其ID
+            if !self.tasks.iter().any(|task| task.id == candidate)
+                && !self.zombies.iter().any(|zombie| zombie.info.id == candidate)
+            {
                 self.next_id_hint = candidate.wrapping_add(1);
                 return candidate;
             }
@@ -112,26 +252,414 @@ impl Executor {
     pub fn spawn(
         cmd: impl Into<String>,
         future: Pin<Box<dyn Future<Output = ExitCode>>>,
+    ) -> TaskId {
+        Self::spawn_with_priority(cmd, Priority::default(), future)
+    }
+
+    /// 以指定优先级创建任务
+    pub fn spawn_with_priority(
+        cmd: impl Into<String>,
+        priority: Priority,
+        future: Pin<Box<dyn Future<Output = ExitCode>>>,
     ) -> TaskId {
         let executor = Executor::get_mut();
         let id = executor.next_id();
-        executor.tasks.push_back(Task::new(id, cmd.into(), future));
-        id
+        let mut task = Task::new(id, cmd.into(), future);
+        task.priority = priority;
+        executor.push_task(task)
+    }
+
+    /// 创建返回任意类型的任务, 通过 await 返回的 JoinHandle 获取任务的返回值
+    ///
+    /// 任务与其他任务一样出现在 ps 中并接收信号, 任务的退出码总是 0
+    pub fn spawn_task<T: 'static>(
+        cmd: impl Into<String>,
+        future: impl Future<Output = T> + 'static,
+    ) -> JoinHandle<T> {
+        let (future, state) = join_handle::wrap(future);
+        let id = Self::spawn(cmd, future);
+        JoinHandle::new(id, state)
     }
 
     pub fn spawn_runnable(runner: Runnable, args: &[String]) -> TaskId {
         let executor = Executor::get_mut();
         let id = executor.next_id();
-        executor
-            .tasks
-            .push_back(Task::new(id, runner.get_name(), runner.run(args)));
+        executor.push_task(Task::new(id, runner.get_name(), runner.run(args)))
+    }
+
+    // 加入新任务, 当前任务作为父任务, 新任务继承父任务的任务组
+    fn push_task(&mut self, mut task: Task) -> TaskId {
+        let id = task.id;
+        if let Some(parent_id) = self.current_task_id {
+            if let Some(parent) = self.task_mut(parent_id) {
+                task.parent = Some(parent_id);
+                task.pgid = parent.pgid;
+                task.stdin = parent.stdin.clone();
+                task.stdout = parent.stdout.clone();
+            }
+        }
+        self.tasks.push_back(task);
+        Self::wake_task(id);
         id
     }
 
+    fn task_mut(&mut self, id: TaskId) -> Option<&mut Task> {
+        self.tasks.iter_mut().find(|task| task.id == id)
+    }
+
+    fn zombie_index(&self, id: TaskId) -> Option<usize> {
+        self.zombies.iter().position(|zombie| zombie.info.id == id)
+    }
+
+    // 任务结束后转为僵尸, 保留退出状态直到被回收
+    fn bury_task(&mut self, id: TaskId) {
+        let task = match self.tasks.iter().position(|task| task.id == id) {
+            Some(index) => self.tasks.remove(index).unwrap(),
+            None => return,
+        };
+        self.zombies.push_back(Zombie {
+            info: task.info(),
+            waiters: task.waiters,
+        });
+        // 超出上限时丢弃最早的无人等待的僵尸, 有等待者的僵尸必须保留
+        while self.zombies.len() > ZOMBIE_LIMIT {
+            match self.zombies.iter().position(|zombie| zombie.waiters == 0) {
+                Some(index) => {
+                    self.zombies.remove(index);
+                }
+                None => break,
+            }
+        }
+    }
+
+    // 增加等待者计数, 有等待者的任务结束后不会被丢弃; 任务不存在时返回 false
+    fn add_waiter(&mut self, id: TaskId) -> bool {
+        if let Some(task) = self.task_mut(id) {
+            task.waiters = task.waiters.wrapping_add(1);
+        } else if let Some(index) = self.zombie_index(id) {
+            self.zombies[index].waiters = self.zombies[index].waiters.wrapping_add(1);
+        } else {
+            return false;
+        }
+        true
+    }
+
+    // 等待者离开, 返回僵尸的退出码; collect 为 true 时最后一个等待者回收僵尸
+    fn release_waiter(&mut self, id: TaskId, collect: bool) -> Option<ExitCode> {
+        if let Some(task) = self.task_mut(id) {
+            task.waiters = task.waiters.wrapping_sub(1);
+            return None;
+        }
+        let index = self.zombie_index(id)?;
+        let zombie = &mut self.zombies[index];
+        zombie.waiters = zombie.waiters.wrapping_sub(1);
+        let exit_code = zombie.exit_code();
+        if collect && zombie.waiters == 0 {
+            self.zombies.remove(index);
+        }
+        Some(exit_code)
+    }
+
+    /// 唤醒任务, 由任务的 Waker 调用, 可在中断中使用
+    ///
+    /// 任务ID先进入待处理唤醒队列, 由调度循环移入就绪队列
+    pub fn wake_task(id: TaskId) {
+        sys::critical_section(|| {
+            let queue = WakeQueue::get_mut();
+            if !queue.ids.push(id) {
+                queue.overflow = true;
+            }
+        });
+    }
+
+    /// 将任务放入对应优先级的就绪队列
+    fn schedule(&mut self, id: TaskId) {
+        if let Some(task) = self.task_mut(id) {
+            if !task.scheduled {
+                task.scheduled = true;
+                let priority = task.priority as usize;
+                self.ready[priority].push_back(id);
+            }
+        }
+    }
+
+    /// 处理待处理的唤醒
+    fn process_wakes(&mut self) {
+        loop {
+            let (id, overflow) = sys::critical_section(|| {
+                let queue = WakeQueue::get_mut();
+                let overflow = core::mem::take(&mut queue.overflow);
+                (queue.ids.pop(), overflow)
+            });
+            if overflow {
+                // 唤醒丢失, 全部任务重新轮询一次
+                let ids: Vec<TaskId> = self.tasks.iter().map(|task| task.id).collect();
+                for id in ids {
+                    self.schedule(id);
+                }
+            }
+            match id {
+                Some(id) => self.schedule(id),
+                None => break,
+            }
+        }
+    }
+
+    /// 注册或更新 key 对应的定时唤醒, 到达 deadline 时唤醒
+    pub(crate) fn register_timer(key: &mut Option<usize>, deadline: Instant, waker: &Waker) {
+        Executor::get_mut().timers.register_keyed(key, deadline, waker);
+    }
+
+    /// 注销 key 对应的定时唤醒
+    pub(crate) fn cancel_timer(key: &mut Option<usize>) {
+        Executor::get_mut().timers.remove(key);
+    }
+
+    /// 最近的定时器到期时间, 没有定时器时返回 None
+    ///
+    /// 空闲/低功耗代码可据此计算 CPU 可以休眠的时长
+    pub fn next_deadline() -> Option<Instant> {
+        Executor::get_mut().next_timer_deadline()
+    }
+
+    fn next_timer_deadline(&self) -> Option<Instant> {
+        match (self.timers.next_deadline(), self.soft_timers.next_deadline()) {
+            (Some(a), Some(b)) => Some(a.min(b)),
+            (a, b) => a.or(b),
+        }
+    }
+
+    /// 添加软件定时器, period 为 None 时只触发一次, 返回定时器ID
+    pub(crate) fn add_soft_timer(
+        deadline: Instant,
+        period: Option<Duration>,
+        callback: SoftTimerCallback,
+    ) -> u32 {
+        Executor::get_mut().soft_timers.add(deadline, period, callback)
+    }
+
+    /// 取消软件定时器, 返回定时器是否存在
+    pub(crate) fn cancel_soft_timer(id: u32) -> bool {
+        Executor::get_mut().soft_timers.cancel(id)
+    }
+
+    pub(crate) fn soft_timer_active(id: u32) -> bool {
+        Executor::get_mut().soft_timers.contains(id)
+    }
+
+    /// 唤醒所有已到期的定时器, 执行已到期的软件定时器回调
+    fn process_timers(&mut self) {
+        let now = Instant::now();
+        if !self.timers.is_empty() {
+            let expired = self.timers.take_expired(now);
+            for waker in expired {
+                waker.wake();
+            }
+        }
+        // 回调中可能添加或取消定时器, 执行期间将回调取出
+        while let Some((id, mut callback)) = self.soft_timers.take_expired(now) {
+            callback();
+            self.soft_timers.restore(id, callback);
+        }
+    }
+
+    /// 没有就绪任务时调用 CPU 空闲钩子, 传入距离下一个定时器到期的时长
+    fn idle(&mut self) {
+        if !SimpleOs::is_initialized() {
+            return;
+        }
+        let max_sleep_ms = match self.next_timer_deadline() {
+            Some(deadline) => {
+                let ms = deadline.duration_since(Instant::now()).as_millis() as u32;
+                if ms == 0 {
+                    return; // 定时器已到期, 无需休眠
+                }
+                Some(ms)
+            }
+            None => None,
+        };
+        // 休眠时间不超过看门狗超时的一半, 保证按时喂狗
+        let max_sleep_ms = match SimpleOs::watchdog() {
+            Some(wdg) => {
+                let limit = (wdg.wdg_get_timeout_ms() / 2).max(1);
+                Some(max_sleep_ms.map_or(limit, |ms| ms.min(limit)))
+            }
+            None => max_sleep_ms,
+        };
+        // 在临界区内确认没有待处理的唤醒再休眠, 避免中断唤醒在检查后丢失
+        sys::critical_section(|| {
+            let queue = WakeQueue::get_mut();
+            if queue.ids.is_empty() && !queue.overflow {
+                SimpleOs::cpu().cpu_idle(max_sleep_ms);
+            }
+        });
+    }
+
+    /// 设置长时间轮询阈值(毫秒), 单次轮询超过该时间时调用长时间轮询处理函数, 0 表示不检测
+    ///
+    /// 协作式调度中, 忘记 await 的任务 (例如调用 `sys::delay_ms` 或忙等循环) 会阻塞所有任务
+    pub fn set_long_poll_threshold_ms(ms: u32) {
+        Self::get_mut().long_poll_threshold_us = ms.saturating_mul(1000);
+    }
+
+    /// 设置长时间轮询处理函数, 替换默认的警告输出
+    pub fn set_long_poll_handler(handler: impl FnMut(TaskId, &str, u32) -> bool + 'static) {
+        Self::get_mut().long_poll_handler = Some(Box::new(handler));
+    }
+
+    // 报告阻塞执行器的任务
+    fn report_long_poll(&mut self, id: TaskId, elapsed_us: u32) {
+        let cmd = match self.task_mut(id) {
+            Some(task) => {
+                task.stats.long_polls = task.stats.long_polls.saturating_add(1);
+                task.cmd.clone()
+            }
+            None => return,
+        };
+        // 报告不属于该任务, 临时清除当前任务, 警告输出到控制台而不是任务重定向的输出
+        let current = self.current_task_id.take();
+        // 处理函数中可能访问执行器, 调用期间将其取出
+        let feed = match self.long_poll_handler.take() {
+            Some(mut handler) => {
+                let feed = handler(id, &cmd, elapsed_us);
+                if self.long_poll_handler.is_none() {
+                    self.long_poll_handler = Some(handler);
+                }
+                feed
+            }
+            None => {
+                println!(
+                    "[WARN] task {} ({}) blocked the executor for {} ms",
+                    id,
+                    cmd,
+                    elapsed_us / 1000
+                );
+                true
+            }
+        };
+        self.current_task_id = current;
+        if !feed {
+            self.watchdog_starved = true;
+        }
+    }
+
+    /// 将当前任务注册到看门狗监控, 之后必须每隔 timeout_ms 内调用 `watchdog_check_in`,
+    /// 否则停止喂硬件看门狗; 重复注册时更新超时时间. 任务结束时自动取消注册
+    pub fn watchdog_register(timeout_ms: u32) -> bool {
+        match Self::current_task_id() {
+            Some(id) => {
+                Self::get_mut().supervisor.register(id, timeout_ms, system_ms64());
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// 当前任务取消看门狗监控
+    pub fn watchdog_unregister() -> bool {
+        match Self::current_task_id() {
+            Some(id) => Self::get_mut().supervisor.unregister(id),
+            None => false,
+        }
+    }
+
+    /// 当前任务向看门狗监控签到, 任务未注册时返回 false
+    pub fn watchdog_check_in() -> bool {
+        match Self::current_task_id() {
+            Some(id) => Self::get_mut().supervisor.check_in(id, system_ms64()),
+            None => false,
+        }
+    }
+
+    // 喂硬件看门狗, 有受监控任务签到超时, 或长时间轮询处理函数要求停止喂狗时不喂狗
+    fn feed_watchdog(&mut self) {
+        let (alive, expired) = self.supervisor.poll(system_ms64());
+        for id in expired {
+            let cmd = self.task_mut(id).map(|task| task.cmd.as_str()).unwrap_or("?");
+            println!("[WARN] task {} ({}) missed watchdog check-in", id, cmd);
+        }
+        if !alive || self.watchdog_starved {
+            return;
+        }
+        if let Some(wdg) = SimpleOs::watchdog() {
+            wdg.wdg_feed();
+        }
+    }
+
+    /// 获取当前任务的局部变量, 不存在时用 init 创建
+    pub(crate) fn task_local_ptr(
+        key: usize,
+        init: impl FnOnce() -> Box<dyn Any>,
+    ) -> Option<*const dyn Any> {
+        let id = Self::current_task_id()?;
+        let task = Self::get_mut().task_mut(id)?;
+        let index = match task.locals.iter().position(|(k, _)| *k == key) {
+            Some(index) => index,
+            None => {
+                task.locals.push((key, init()));
+                task.locals.len() - 1
+            }
+        };
+        Some(task.locals[index].1.as_ref() as *const dyn Any)
+    }
+
+    /// 标记任务结束: 释放 future 和局部变量, 唤醒等待者, 无等待者时移除任务
+    ///
+    /// 释放顺序: future (其中的守卫按 Rust 析构顺序执行), 清理函数 (按注册的逆序),
+    /// 任务局部变量, 最后唤醒等待者
+    fn finish_task(&mut self, id: TaskId, exit_code: ExitCode) {
+        let (future, mut cleanups, locals, stdio, wakers) = match self.task_mut(id) {
+            Some(task) => {
+                task.exited = Some(exit_code);
+                task.paused = false;
+                task.kill_deadline = None;
+                (
+                    task.future.take(),
+                    core::mem::take(&mut task.cleanups),
+                    core::mem::take(&mut task.locals),
+                    (task.stdin.take(), task.stdout.take()),
+                    core::mem::take(&mut task.exit_wakers),
+                )
+            }
+            None => return,
+        };
+        self.supervisor.unregister(id);
+        // 子任务交给祖父任务
+        let parent = self.task_mut(id).and_then(|task| task.parent);
+        for child in self.tasks.iter_mut().filter(|task| task.parent == Some(id)) {
+            child.parent = parent;
+        }
+        for zombie in self.zombies.iter_mut().filter(|z| z.info.parent == Some(id)) {
+            zombie.info.parent = parent;
+        }
+        self.bury_task(id);
+        // 通知父任务的 wait_any
+        if let Some(waker) = parent.and_then(|p| self.task_mut(p)).and_then(|p| p.child_waker.take()) {
+            waker.wake();
+        }
+        // future 的析构可能再次访问执行器, 放在最后释放, 局部变量在 future 之后释放
+        drop(future);
+        while let Some(cleanup) = cleanups.pop() {
+            cleanup();
+        }
+        drop(locals);
+        // print! 不刷新重定向的输出, 任务结束时刷新
+        if let Some(stdout) = &stdio.1 {
+            if let Ok(mut stdout) = stdout.try_borrow_mut() {
+                stdout.flush();
+            }
+        }
+        // 最后一个持有者释放时关闭标准输入输出, 例如管道写端关闭后读端读到结尾
+        drop(stdio);
+        for waker in wakers {
+            waker.wake();
+        }
+    }
+
     pub fn default_signal_handler(signal: Signal) -> SignalAction {
         match signal {
             Signal::SIGINT | Signal::SIGTERM => SignalAction::Terminate(-1),
-            Signal::SIGKILL => SignalAction::Terminate(-9),
+            Signal::SIGKILL => SignalAction::Terminate(KILL_EXIT_CODE),
             Signal::SIGSTOP => SignalAction::Pause,
             Signal::SIGCONT => SignalAction::Continue,
             Signal::SIGUSR(_) => SignalAction::Ignore,
@@ -142,38 +670,56 @@ impl Executor {
     pub fn run() {
         let executor = Executor::get_mut();
 
-        // 创建一个简单的waker
-        let waker = unsafe { Waker::from_raw(dummy_raw_waker()) };
-        let mut context = Context::from_waker(&waker);
-
         loop {
-            let task: &mut Task = {
-                if let Some(task) = executor.tasks.front_mut() {
-                    task
-                } else {
-                    break; // 没有更多任务
+            if executor.tasks.is_empty() {
+                break; // 没有更多任务
+            }
+
+            if SimpleOs::is_initialized() {
+                // 保持64位扩展计数器跟踪回绕
+                sys::get_system_ms64();
+                executor.feed_watchdog();
+                executor.process_timers();
+            }
+            executor.process_wakes();
+
+            // 从最高优先级开始取出下一个就绪任务, 没有就绪任务时进入空闲
+            let id = match executor.ready.iter_mut().rev().find_map(|queue| queue.pop_front()) {
+                Some(id) => id,
+                None => {
+                    executor.idle();
+                    continue;
                 }
             };
 
-            // 任务已退出
-            if task.exited.is_some() {
-                if task.waiters == 0 {
-                    executor.tasks.pop_front(); // 任务无等待者，移除任务
-                } else {
-                    executor.tasks.rotate_left(1); // 继续下一个任务
+            // 设置当前任务ID, 用于 exit() 等函数使用
+            executor.current_task_id = Some(id);
+            let grace = Duration::from_millis(executor.cancel_grace_ms as u64);
+            let mut kill_timer = None;
+
+            let task = match executor.task_mut(id) {
+                Some(task) if task.exited.is_none() => task,
+                _ => {
+                    // 任务已被移除或已退出
+                    executor.current_task_id = None;
+                    continue;
                 }
-                continue;
-            }
+            };
+            task.scheduled = false;
 
-            // 设置当前任务ID, 用于 exit() 等函数使用
-            executor.current_task_id = Some(task.id);
+            // 协作式终止的宽限期已过, 升级为 SIGKILL
+            let mut terminated = None;
+            if task.kill_deadline.is_some_and(|deadline| deadline <= Instant::now()) {
+                task.pending_signals.clear();
+                terminated = Some(KILL_EXIT_CODE);
+            }
 
             // 处理待处理的信号
             while let Some(signal) = task.pending_signals.pop() {
                 let action = if let Some(ref handler) = task.signal_handler {
                     match signal {
                         // SIGKILL 和 SIGSTOP 不能被捕获
-                        Signal::SIGKILL => SignalAction::Terminate(-9),
+                        Signal::SIGKILL => SignalAction::Terminate(KILL_EXIT_CODE),
                         Signal::SIGSTOP => SignalAction::Pause,
                         Signal::SIGNULL => SignalAction::Ignore,
                         _ => handler(signal),
@@ -183,9 +729,28 @@ impl Executor {
                 };
 
                 match action {
+                    // 任务持有取消令牌时先取消令牌, 宽限期内继续轮询, 由任务自行清理后返回
+                    SignalAction::Terminate(code)
+                        if signal != Signal::SIGKILL
+                            && task.cancel_token.is_some()
+                            && SimpleOs::is_initialized() =>
+                    {
+                        if task.terminating.is_none() {
+                            task.terminating = Some(code);
+                            task.paused = false;
+                            if let Some(token) = task.cancel_token.as_ref() {
+                                token.cancel();
+                            }
+                            let deadline = Instant::now() + grace;
+                            if task.kill_deadline.is_none_or(|d| deadline < d) {
+                                task.kill_deadline = Some(deadline);
+                                kill_timer = Some(deadline);
+                            }
+                        }
+                        continue;
+                    }
                     SignalAction::Terminate(code) => {
-                        task.exited = Some(code);
-                        task.paused = false;
+                        terminated = Some(code);
                         break;
                     }
                     SignalAction::Ignore => continue,
@@ -200,25 +765,54 @@ impl Executor {
                 }
             }
 
-            // 如果任务被暂停，跳过执行
-            if !task.paused {
-                // 轮询任务
-                match task.future.as_mut().poll(&mut context) {
-                    Poll::Ready(exit_code) => {
-                        task.exited = Some(exit_code); // 任务完成，设置退出码
-                        if task.waiters == 0 {
-                            // 任务无等待者，移除任务
-                            executor.tasks.pop_front();
-                        } else {
-                            executor.tasks.rotate_left(1); // 继续下一个任务
-                        }
+            if let Some(code) = terminated {
+                executor.finish_task(id, code);
+            } else if !task.paused {
+                // 轮询任务, 轮询期间将 future 取出, 避免任务队列变化时引用失效
+                if let Some(mut future) = task.future.take() {
+                    let waker = task_waker(id);
+                    let mut context = Context::from_waker(&waker);
+                    let start_us = poll_time_us();
+                    let result = future.as_mut().poll(&mut context);
+                    let elapsed_us = poll_time_us().wrapping_sub(start_us);
+                    if let Some(task) = executor.task_mut(id) {
+                        let stats = &mut task.stats;
+                        stats.poll_count = stats.poll_count.wrapping_add(1);
+                        stats.total_poll_us += elapsed_us as u64;
+                        stats.max_poll_us = stats.max_poll_us.max(elapsed_us);
+                        stats.last_run_ms = system_ms64();
                     }
-                    Poll::Pending => {
-                        executor.tasks.rotate_left(1); // 任务未完成，移到队列末尾
+                    let threshold_us = executor.long_poll_threshold_us;
+                    if threshold_us != 0 && elapsed_us >= threshold_us {
+                        executor.report_long_poll(id, elapsed_us);
+                    }
+                    match result {
+                        Poll::Ready(exit_code) => {
+                            // 协作式终止期间返回时, 使用终止信号对应的退出码
+                            let exit_code = executor
+                                .task_mut(id)
+                                .and_then(|task| task.terminating)
+                                .unwrap_or(exit_code);
+                            executor.finish_task(id, exit_code); // 任务完成，设置退出码
+                        }
+                        Poll::Pending => {
+                            if let Some(task) = executor.task_mut(id) {
+                                task.future = Some(future);
+                                // 任务在轮询中调用了 exit()
+                                if let Some(exit_code) = task.exited {
+                                    executor.finish_task(id, exit_code);
+                                }
+                            }
+                        }
                     }
                 }
             }
 
+            // 宽限期到期时唤醒任务, 以便升级为 SIGKILL
+            if let Some(deadline) = kill_timer {
+                executor.timers.register(deadline, &task_waker(id));
+            }
+
             // 清除当前任务
             let _ = executor.current_task_id.take().unwrap();
         }
@@ -245,14 +839,98 @@ impl Executor {
             .collect()
     }
 
+    /// 获取任务信息列表
+    ///
+    /// 包括已结束未回收的任务, 其 exit_code 不为 None
+    pub fn task_info_list() -> Vec<TaskInfo> {
+        let executor = Self::get_mut();
+        executor
+            .tasks
+            .iter()
+            .filter(|task| task.exited.is_none())
+            .map(|task| task.info())
+            .chain(executor.zombies.iter().map(|zombie| zombie.info.clone()))
+            .collect()
+    }
+
+    /// 修改当前任务的优先级, 在下次被唤醒时生效
+    pub fn set_priority(priority: Priority) -> bool {
+        match Self::current_task_id() {
+            Some(id) => Self::set_task_priority(id, priority),
+            None => false,
+        }
+    }
+
+    /// 修改指定任务的优先级, 在下次被唤醒时生效
+    pub fn set_task_priority(id: TaskId, priority: Priority) -> bool {
+        if let Some(task) = Self::get_mut().task_mut(id) {
+            task.priority = priority;
+            true
+        } else {
+            false
+        }
+    }
+
+    /// 获取任务优先级
+    pub fn priority(id: TaskId) -> Option<Priority> {
+        Self::get_mut().task_mut(id).map(|task| task.priority)
+    }
+
     /// 获取当前运行任务ID
     pub fn current_task_id() -> Option<TaskId> {
         Self::get_mut().current_task_id
     }
 
-    /// 杀死任务, 如果需要SIGKILL请使用 send_signal
+    /// 杀死任务: 先发送 SIGTERM, 任务在宽限期内未结束时升级为 SIGKILL,
+    /// 立即强制终止请使用 send_signal 发送 SIGKILL
     pub fn kill(id: TaskId) -> bool {
-        Self::send_signal(id, Signal::SIGTERM)
+        if !Self::send_signal(id, Signal::SIGTERM) {
+            return false;
+        }
+        if SimpleOs::is_initialized() {
+            let executor = Self::get_mut();
+            let deadline = Instant::now() + Duration::from_millis(executor.cancel_grace_ms as u64);
+            if let Some(task) = executor.task_mut(id) {
+                if task.kill_deadline.is_none_or(|d| deadline < d) {
+                    task.kill_deadline = Some(deadline);
+                    executor.timers.register(deadline, &task_waker(id));
+                }
+            }
+        }
+        true
+    }
+
+    /// 设置协作式终止的宽限期(毫秒), 超时后强制终止
+    pub fn set_cancel_grace_ms(ms: u32) {
+        Self::get_mut().cancel_grace_ms = ms;
+    }
+
+    /// 获取当前任务的取消令牌, 不在任务上下文中时返回 None
+    ///
+    /// 获取令牌后, SIGINT/SIGTERM 等终止信号会先取消令牌, 任务需在宽限期内自行返回
+    pub fn cancellation_token() -> Option<CancellationToken> {
+        let id = Self::current_task_id()?;
+        let task = Self::get_mut().task_mut(id)?;
+        Some(
+            task.cancel_token
+                .get_or_insert_with(CancellationToken::new)
+                .clone(),
+        )
+    }
+
+    /// 为当前任务注册清理函数, 任务以任何方式结束时在 future 释放后按注册的逆序执行
+    pub fn add_cleanup(cleanup: impl FnOnce() + 'static) -> bool {
+        let task = match Self::current_task_id() {
+            Some(id) => Self::get_mut().task_mut(id),
+            None => None,
+        };
+        match task {
+            Some(task) => {
+                task.cleanups.push(Box::new(cleanup));
+                true
+            }
+            None => false,
+        }
     }
 
     /// 结束当前任务, 设置 exit 标志
@@ -290,10 +968,16 @@ impl Executor {
         // 检查任务队列中是否存在该任务
         let executor = Executor::get_mut();
         // let tasks = executor.tasks.borrow();
-        executor.tasks.iter().any(|task| task.id == id)
+        executor
+            .tasks
+            .iter()
+            .any(|task| task.id == id && task.exited.is_none())
     }
 
-    /// 等待任务完成
+    /// 等待任务完成并回收
+    ///
+    /// 任务已结束时立即返回保留的退出状态; 多个任务等待同一任务时都能获得退出状态,
+    /// 由最后一个等待者回收
     #[allow(unused)]
     pub async fn wait(id: TaskId) -> ExitStatus {
         // 获取当前任务id
@@ -310,43 +994,226 @@ impl Executor {
             return ExitStatus::ErrorPid;
         }
 
-        // 检查任务是否存在, 增加等待者计数
-        if let Some(task) = Self::get_mut().tasks.iter_mut().find(|t| t.id == id) {
-            task.waiters = task.waiters.wrapping_add(1);
-        } else {
+        // 检查任务是否存在, 增加等待者计数, 有等待者的任务结束后不会被丢弃
+        if !Self::get_mut().add_waiter(id) {
             return ExitStatus::NotExist;
         }
 
-        // 轮询等待结果
-        loop {
-            // 让出当前任务，允许其他任务运行
-            sys::yield_now().await;
-
-            // 检查目标任务的状态
-            if let Some(task) = Self::get_mut().tasks.iter_mut().find(|t| t.id == id) {
-                if let Some(exit_code) = task.exited {
-                    // 目标任务已退出，减少等待者计数
-                    task.waiters = task.waiters.wrapping_sub(1);
+        // 等待被取消时减少等待者计数
+        struct WaitGuard {
+            id: TaskId,
+            done: bool,
+        }
+        impl Drop for WaitGuard {
+            fn drop(&mut self) {
+                if !self.done {
+                    Executor::get_mut().release_waiter(self.id, false);
+                }
+            }
+        }
+        let mut guard = WaitGuard { id, done: false };
 
-                    // 任务已退出，返回结果
-                    return ExitStatus::Exited(exit_code);
+        // 注册唤醒器, 目标任务结束时被唤醒
+        poll_fn(move |cx| {
+            let executor = Self::get_mut();
+            if let Some(task) = executor.task_mut(id) {
+                if !task.exit_wakers.iter().any(|w| w.will_wake(cx.waker())) {
+                    task.exit_wakers.push(cx.waker().clone());
                 }
-            } else {
-                // 任务不存在, 不应该发生
-                return ExitStatus::Aborted;
+                return Poll::Pending;
             }
+            // 任务已结束, 僵尸在所有等待者离开前不会被丢弃
+            guard.done = true;
+            match executor.release_waiter(id, true) {
+                Some(exit_code) => Poll::Ready(ExitStatus::Exited(exit_code)),
+                None => unreachable!("waited task {} vanished", id),
+            }
+        })
+        .await
+    }
+
+    /// 保留任务的退出状态, 用于轮询任务状态而不 await wait 的场合
+    ///
+    /// 任务结束后僵尸不会被丢弃, 任务ID也不会被复用, 直到调用 release; 任务不存在时返回 false
+    pub fn hold(id: TaskId) -> bool {
+        Self::get_mut().add_waiter(id)
+    }
+
+    /// 释放 hold, 任务已结束时回收并返回其退出码; 任务未结束时返回 None
+    ///
+    /// 有其他等待者时只返回退出码, 由最后一个等待者回收
+    pub fn release(id: TaskId) -> Option<ExitCode> {
+        Self::get_mut().release_waiter(id, true)
+    }
+
+    // 释放 hold 但不回收, 僵尸留给 wait_any 或 reap
+    pub(crate) fn unhold(id: TaskId) {
+        Self::get_mut().release_waiter(id, false);
+    }
+
+    /// 回收已结束的任务, 返回其退出码; 任务未结束, 不存在或有其他任务在等待时返回 None
+    pub fn reap(id: TaskId) -> Option<ExitCode> {
+        let executor = Self::get_mut();
+        let index = executor.zombie_index(id)?;
+        if executor.zombies[index].waiters != 0 {
+            return None;
         }
+        executor.zombies.remove(index).map(|zombie| zombie.exit_code())
+    }
+
+    /// 等待当前任务的任意一个子任务结束并回收, 返回子任务ID和退出码
+    ///
+    /// 已结束的子任务按结束顺序返回; 没有子任务时返回 None.
+    /// 正在被其他任务 wait 的子任务由那些任务回收, 不会被返回
+    pub async fn wait_any() -> Option<(TaskId, ExitCode)> {
+        let current_id = Self::current_task_id()?;
+        poll_fn(|cx| {
+            let executor = Self::get_mut();
+            if let Some(index) = executor
+                .zombies
+                .iter()
+                .position(|z| z.info.parent == Some(current_id) && z.waiters == 0)
+            {
+                let zombie = executor.zombies.remove(index).unwrap();
+                return Poll::Ready(Some((zombie.info.id, zombie.exit_code())));
+            }
+            if !executor
+                .tasks
+                .iter()
+                .any(|task| task.parent == Some(current_id) && task.exited.is_none())
+            {
+                return Poll::Ready(None);
+            }
+            if let Some(task) = executor.task_mut(current_id) {
+                task.child_waker = Some(cx.waker().clone());
+            }
+            Poll::Pending
+        })
+        .await
     }
 
     /// 向任务发送信号
     pub fn send_signal(target_id: TaskId, signal: Signal) -> bool {
-        if let Some(task) = Self::get_mut().tasks.iter_mut().find(|t| t.id == target_id) {
+        if let Some(task) = Self::get_mut().task_mut(target_id) {
             task.pending_signals.push(signal);
+            // 唤醒任务以便处理信号
+            Self::wake_task(target_id);
             return true;
         }
         false
     }
 
+    /// 向任务组内所有任务发送信号, 返回收到信号的任务数量
+    pub fn send_group_signal(pgid: TaskId, signal: Signal) -> usize {
+        Self::group_members(pgid)
+            .into_iter()
+            .filter(|id| Self::send_signal(*id, signal))
+            .count()
+    }
+
+    /// 杀死任务组内所有任务, 规则同 kill, 返回被杀死的任务数量
+    pub fn kill_group(pgid: TaskId) -> usize {
+        Self::group_members(pgid)
+            .into_iter()
+            .filter(|id| Self::kill(*id))
+            .count()
+    }
+
+    /// 杀死任务, kill_children 为 true 时同时杀死所有子孙任务
+    pub fn kill_tree(id: TaskId, kill_children: bool) -> bool {
+        if kill_children {
+            for child in Self::children_of(id) {
+                Self::kill_tree(child, true);
+            }
+        }
+        Self::kill(id)
+    }
+
+    // 任务组内未结束的任务
+    fn group_members(pgid: TaskId) -> Vec<TaskId> {
+        Self::get_mut()
+            .tasks
+            .iter()
+            .filter(|task| task.pgid == pgid && task.exited.is_none())
+            .map(|task| task.id)
+            .collect()
+    }
+
+    /// 设置任务的标准输入, None 表示终端, 之后创建的子任务继承此设置
+    pub fn set_stdin(id: TaskId, stdin: Option<Stdin>) -> bool {
+        match Self::get_mut().task_mut(id) {
+            Some(task) if task.exited.is_none() => {
+                task.stdin = stdin;
+                true
+            }
+            _ => false,
+        }
+    }
+
+    /// 设置任务的标准输出, None 表示终端, 之后创建的子任务继承此设置
+    pub fn set_stdout(id: TaskId, stdout: Option<Stdout>) -> bool {
+        match Self::get_mut().task_mut(id) {
+            Some(task) if task.exited.is_none() => {
+                task.stdout = stdout;
+                true
+            }
+            _ => false,
+        }
+    }
+
+    /// 当前任务的标准输入, 未重定向或不在任务上下文中时返回 None
+    pub fn stdin() -> Option<Stdin> {
+        let id = Self::current_task_id()?;
+        Self::get_mut().task_mut(id)?.stdin.clone()
+    }
+
+    /// 当前任务的标准输出, 未重定向或不在任务上下文中时返回 None
+    pub fn stdout() -> Option<Stdout> {
+        let id = Self::current_task_id()?;
+        Self::get_mut().task_mut(id)?.stdout.clone()
+    }
+
+    /// 获取任务的父任务ID
+    pub fn parent_id(id: TaskId) -> Option<TaskId> {
+        Self::get_mut().task_mut(id).and_then(|task| task.parent)
+    }
+
+    /// 获取任务的任务组ID
+    pub fn pgid(id: TaskId) -> Option<TaskId> {
+        Self::get_mut().task_mut(id).map(|task| task.pgid)
+    }
+
+    /// 设置任务的任务组, pgid 与任务ID相同时任务成为新任务组的组长
+    ///
+    /// 之后创建的子任务继承该任务组
+    pub fn set_pgid(id: TaskId, pgid: TaskId) -> bool {
+        match Self::get_mut().task_mut(id) {
+            Some(task) => {
+                task.pgid = pgid;
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// 列出任务的未结束的直接子任务
+    pub fn children_of(id: TaskId) -> Vec<TaskId> {
+        Self::get_mut()
+            .tasks
+            .iter()
+            .filter(|task| task.parent == Some(id) && task.exited.is_none())
+            .map(|task| task.id)
+            .collect()
+    }
+
+    /// 列出当前任务的子任务
+    pub fn children() -> Vec<TaskId> {
+        match Self::current_task_id() {
+            Some(id) => Self::children_of(id),
+            None => Vec::new(),
+        }
+    }
+
     /// 注册信号处理器
     pub fn register_signal_handler<F>(handler: F)
     where
@@ -366,14 +1233,42 @@ impl Executor {
     }
 }
 
-// 简单的 dummy waker
-fn dummy_raw_waker() -> RawWaker {
-    fn clone(_: *const ()) -> RawWaker {
-        dummy_raw_waker()
+// 系统毫秒计数, 系统未初始化时为 0
+fn system_ms64() -> u64 {
+    if SimpleOs::is_initialized() {
+        sys::get_system_ms64()
+    } else {
+        0
+    }
+}
+
+// 用于统计轮询耗时的微秒计数, 优先使用 CPU 提供的高精度计数
+fn poll_time_us() -> u32 {
+    if !SimpleOs::is_initialized() {
+        return 0;
+    }
+    match SimpleOs::cpu().cpu_get_time_us() {
+        Some(us) => us,
+        None => sys::get_system_ms().wrapping_mul(1000),
+    }
+}
+
+// 任务唤醒器, 数据指针中保存任务ID, 唤醒时将任务放入就绪队列
+fn task_waker(id: TaskId) -> Waker {
+    unsafe { Waker::from_raw(task_raw_waker(id as usize as *const ())) }
+}
+
+fn task_raw_waker(data: *const ()) -> RawWaker {
+    fn clone(data: *const ()) -> RawWaker {
+        task_raw_waker(data)
+    }
+    fn wake(data: *const ()) {
+        Executor::wake_task(data as usize as TaskId);
+    }
+    fn wake_by_ref(data: *const ()) {
+        Executor::wake_task(data as usize as TaskId);
     }
-    fn wake(_: *const ()) {}
-    fn wake_by_ref(_: *const ()) {}
     fn drop(_: *const ()) {}
     const VTABLE: RawWakerVTable = RawWakerVTable::new(clone, wake, wake_by_ref, drop);
-    RawWaker::new(core::ptr::null::<()>(), &VTABLE)
+    RawWaker::new(data, &VTABLE)
 }
@@ -0,0 +1,124 @@
+use alloc::boxed::Box;
+use alloc::rc::Rc;
+use core::cell::RefCell;
+use core::future::Future;
+use core::pin::Pin;
+use core::task::{Context, Poll, Waker};
+
+use crate::executor::{Executor, ExitCode, Signal, TaskId};
+
+/// 任务未返回结果就被终止 (abort, kill 等)
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct JoinError;
+
+struct JoinState<T> {
+    output: Option<T>,
+    finished: bool,
+    waker: Option<Waker>,
+}
+
+// 任务的 future 被释放时 (正常完成或被终止) 通知 JoinHandle
+struct Completion<T>(Rc<RefCell<JoinState<T>>>);
+
+impl<T> Drop for Completion<T> {
+    fn drop(&mut self) {
+        let waker = {
+            let mut state = self.0.borrow_mut();
+            state.finished = true;
+            state.waker.take()
+        };
+        if let Some(waker) = waker {
+            waker.wake();
+        }
+    }
+}
+
+// 将返回任意类型的 future 包装为任务, 返回值保存到共享状态中
+pub(crate) fn wrap<T: 'static>(
+    future: impl Future<Output = T> + 'static,
+) -> (Pin<Box<dyn Future<Output = ExitCode>>>, JoinHandleState<T>) {
+    let state = Rc::new(RefCell::new(JoinState {
+        output: None,
+        finished: false,
+        waker: None,
+    }));
+    let completion = Completion(state.clone());
+    let task = Box::pin(async move {
+        let output = future.await;
+        completion.0.borrow_mut().output = Some(output);
+        drop(completion);
+        0
+    });
+    (task, JoinHandleState(state))
+}
+
+pub(crate) struct JoinHandleState<T>(Rc<RefCell<JoinState<T>>>);
+
+/// 由 `Executor::spawn_task` 返回, await 得到任务的返回值
+///
+/// 丢弃 JoinHandle 不会终止任务
+///
+/// JoinHandle 存在期间保留任务的退出状态, 任务结束后不会被丢弃, 任务ID也不会被复用
+pub struct JoinHandle<T> {
+    id: TaskId,
+    state: Rc<RefCell<JoinState<T>>>,
+    held: bool, // 是否仍持有任务的退出状态
+}
+
+impl<T> JoinHandle<T> {
+    pub(crate) fn new(id: TaskId, state: JoinHandleState<T>) -> Self {
+        let held = Executor::hold(id);
+        JoinHandle {
+            id,
+            state: state.0,
+            held,
+        }
+    }
+
+    pub fn id(&self) -> TaskId {
+        self.id
+    }
+
+    /// 立即终止任务 (SIGKILL), await 将返回 JoinError
+    pub fn abort(&self) {
+        if !self.is_finished() {
+            Executor::send_signal(self.id, Signal::SIGKILL);
+        }
+    }
+
+    /// 任务是否已结束
+    pub fn is_finished(&self) -> bool {
+        self.state.borrow().finished
+    }
+}
+
+impl<T> Future for JoinHandle<T> {
+    type Output = Result<T, JoinError>;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let this = self.get_mut();
+        let mut state = this.state.borrow_mut();
+        if !state.finished {
+            state.waker = Some(cx.waker().clone());
+            return Poll::Pending;
+        }
+        // 返回值已由 JoinHandle 取走, 回收任务的退出状态
+        if this.held {
+            this.held = false;
+            Executor::release(this.id);
+        }
+        match state.output.take() {
+            Some(output) => Poll::Ready(Ok(output)),
+            None => Poll::Ready(Err(JoinError)),
+        }
+    }
+}
+
+impl<T> Drop for JoinHandle<T> {
+    fn drop(&mut self) {
+        // 未 await 就丢弃时只释放退出状态, 由父任务的 wait_any 或 reap 回收
+        if self.held {
+            Executor::unhold(self.id);
+        }
+    }
+}
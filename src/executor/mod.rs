@@ -1,5 +1,9 @@
 mod executor;
+mod join_handle;
 mod runnable;
+mod supervisor;
+mod task_local;
+mod timer;
 
 #[cfg(all(feature = "panic-handler", not(test)))]
 mod panic_handler;
@@ -19,3 +23,11 @@ pub use executor::*;
 #[allow(unused)]
 pub use runnable::*;
 
+pub use join_handle::{JoinError, JoinHandle};
+
+pub use task_local::LocalKey;
+
+pub(crate) use timer::TimerHandle;
+
+pub use crate::task_local;
+
@@ -0,0 +1,78 @@
+use alloc::vec::Vec;
+
+use crate::executor::TaskId;
+
+// 受监控的任务
+struct Entry {
+    id: TaskId,
+    timeout_ms: u32,    // 签到超时时间
+    last_check_in: u64, // 最近一次签到时间(系统毫秒)
+    reported: bool,     // 本次超时已报告
+}
+
+/// 任务存活监控, 受监控的任务必须在各自的超时时间内签到
+pub struct Supervisor {
+    entries: Vec<Entry>,
+}
+
+impl Supervisor {
+    pub const fn new() -> Self {
+        Supervisor {
+            entries: Vec::new(),
+        }
+    }
+
+    /// 注册任务, 已注册时更新超时时间, 并视为在 now 签到
+    pub fn register(&mut self, id: TaskId, timeout_ms: u32, now: u64) {
+        match self.entries.iter_mut().find(|e| e.id == id) {
+            Some(entry) => {
+                entry.timeout_ms = timeout_ms;
+                entry.last_check_in = now;
+                entry.reported = false;
+            }
+            None => self.entries.push(Entry {
+                id,
+                timeout_ms,
+                last_check_in: now,
+                reported: false,
+            }),
+        }
+    }
+
+    /// 取消注册, 返回任务是否已注册
+    pub fn unregister(&mut self, id: TaskId) -> bool {
+        let len = self.entries.len();
+        self.entries.retain(|e| e.id != id);
+        self.entries.len() != len
+    }
+
+    /// 签到, 返回任务是否已注册
+    pub fn check_in(&mut self, id: TaskId, now: u64) -> bool {
+        match self.entries.iter_mut().find(|e| e.id == id) {
+            Some(entry) => {
+                entry.last_check_in = now;
+                entry.reported = false;
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// 检查是否所有任务都已按时签到
+    ///
+    /// 返回 (全部存活, 新超时的任务列表), 每次超时只报告一次
+    pub fn poll(&mut self, now: u64) -> (bool, Vec<TaskId>) {
+        let mut alive = true;
+        let mut expired = Vec::new();
+        for entry in self.entries.iter_mut() {
+            if now.saturating_sub(entry.last_check_in) > entry.timeout_ms as u64 {
+                alive = false;
+                if !entry.reported {
+                    entry.reported = true;
+                    expired.push(entry.id);
+                }
+            }
+        }
+        (alive, expired)
+    }
+}
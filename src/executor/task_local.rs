@@ -0,0 +1,103 @@
+use alloc::boxed::Box;
+use core::cell::RefCell;
+
+use crate::executor::Executor;
+
+/// 任务局部变量, 每个任务持有独立的值, 首次访问时初始化, 任务结束时释放
+///
+/// 使用 `task_local!` 宏声明
+pub struct LocalKey<T: 'static> {
+    init: fn() -> T,
+}
+
+impl<T: 'static> LocalKey<T> {
+    #[doc(hidden)]
+    pub const fn new(init: fn() -> T) -> Self {
+        LocalKey { init }
+    }
+
+    // 以静态变量的地址作为键
+    fn key(&'static self) -> usize {
+        self as *const Self as usize
+    }
+
+    // 获取当前任务的值所在的 RefCell, 不在任务上下文中时返回 None
+    fn cell(&'static self) -> Option<&'static RefCell<T>> {
+        let init = self.init;
+        let ptr = Executor::task_local_ptr(self.key(), || Box::new(RefCell::new(init())))?;
+        // 值保存在堆上, 任务结束前地址不变; 可变访问由 RefCell 检查
+        unsafe { (*ptr).downcast_ref::<RefCell<T>>() }
+    }
+
+    /// 访问当前任务的值, 不在任务上下文中时返回 None
+    ///
+    /// 闭包中再次访问同一个变量会 panic
+    pub fn try_with_mut<R>(&'static self, f: impl FnOnce(&mut T) -> R) -> Option<R> {
+        let cell = self.cell()?;
+        let mut value = cell
+            .try_borrow_mut()
+            .unwrap_or_else(|_| panic!("task_local already borrowed"));
+        Some(f(&mut value))
+    }
+
+    /// 访问当前任务的值, 不在任务上下文中时 panic
+    pub fn with_mut<R>(&'static self, f: impl FnOnce(&mut T) -> R) -> R {
+        match self.try_with_mut(f) {
+            Some(result) => result,
+            None => panic!("task_local accessed outside of task context"),
+        }
+    }
+
+    /// 只读访问当前任务的值, 不在任务上下文中时 panic
+    ///
+    /// 闭包中可以再次只读访问同一个变量, 可变访问会 panic
+    pub fn with<R>(&'static self, f: impl FnOnce(&T) -> R) -> R {
+        let cell = match self.cell() {
+            Some(cell) => cell,
+            None => panic!("task_local accessed outside of task context"),
+        };
+        let value = cell
+            .try_borrow()
+            .unwrap_or_else(|_| panic!("task_local already mutably borrowed"));
+        f(&value)
+    }
+
+    /// 设置当前任务的值
+    pub fn set(&'static self, value: T) {
+        self.with_mut(|v| *v = value);
+    }
+
+    /// 获取当前任务的值的副本
+    pub fn get(&'static self) -> T
+    where
+        T: Clone,
+    {
+        self.with(|v| v.clone())
+    }
+}
+
+/// 声明任务局部变量
+///
+/// ```ignore
+/// task_local! {
+///     static LOG_TAG: String = String::from("main");
+/// }
+/// LOG_TAG.set(String::from("modbus"));
+/// ```
+#[macro_export]
+macro_rules! task_local {
+    () => {};
+    ($(#[$attr:meta])* $vis:vis static $name:ident: $t:ty = $init:expr; $($rest:tt)*) => {
+        $(#[$attr])*
+        $vis static $name: $crate::executor::LocalKey<$t> = $crate::executor::LocalKey::new({
+            fn __init() -> $t {
+                $init
+            }
+            __init
+        });
+        $crate::task_local!($($rest)*);
+    };
+    ($(#[$attr:meta])* $vis:vis static $name:ident: $t:ty = $init:expr) => {
+        $crate::task_local!($(#[$attr])* $vis static $name: $t = $init;);
+    };
+}
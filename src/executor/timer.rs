@@ -0,0 +1,227 @@
+use alloc::boxed::Box;
+use alloc::vec::Vec;
+use core::task::Waker;
+
+use crate::executor::Executor;
+use crate::sys::{Duration, Instant};
+
+struct TimerEntry {
+    deadline: Instant,
+    key: Option<usize>, // 由 TimerHandle 登记的定时器可按 key 注销
+    waker: Waker,
+}
+
+/// 定时器队列, 按到期时间升序保存等待中的唤醒器
+///
+/// 到期时间按回绕安全的方式比较, 队列中的时间点跨度需小于 MAX_DURATION_MS
+pub struct TimerQueue {
+    entries: Vec<TimerEntry>,
+    next_key: usize,
+}
+
+impl TimerQueue {
+    pub const fn new() -> Self {
+        TimerQueue {
+            entries: Vec::new(),
+            next_key: 0,
+        }
+    }
+
+    fn insert(&mut self, entry: TimerEntry) {
+        let index = self.entries.partition_point(|e| e.deadline <= entry.deadline);
+        self.entries.insert(index, entry);
+    }
+
+    /// 注册一个定时器, 相同到期时间和唤醒器的重复注册会被忽略
+    pub fn register(&mut self, deadline: Instant, waker: &Waker) {
+        let index = self.entries.partition_point(|e| e.deadline <= deadline);
+        if self.entries[..index]
+            .iter()
+            .rev()
+            .take_while(|e| e.deadline == deadline)
+            .any(|e| e.waker.will_wake(waker))
+        {
+            return;
+        }
+        self.entries.insert(
+            index,
+            TimerEntry {
+                deadline,
+                key: None,
+                waker: waker.clone(),
+            },
+        );
+    }
+
+    /// 注册或更新 key 对应的定时器, key 为 None 时分配新的 key
+    pub fn register_keyed(&mut self, key: &mut Option<usize>, deadline: Instant, waker: &Waker) {
+        let k = match *key {
+            Some(k) => {
+                if let Some(index) = self.entries.iter().position(|e| e.key == Some(k)) {
+                    let entry = &mut self.entries[index];
+                    if entry.deadline == deadline {
+                        if !entry.waker.will_wake(waker) {
+                            entry.waker = waker.clone();
+                        }
+                        return;
+                    }
+                    self.entries.remove(index);
+                }
+                k
+            }
+            None => {
+                let k = self.next_key;
+                self.next_key = self.next_key.wrapping_add(1);
+                *key = Some(k);
+                k
+            }
+        };
+        self.insert(TimerEntry {
+            deadline,
+            key: Some(k),
+            waker: waker.clone(),
+        });
+    }
+
+    /// 注销 key 对应的定时器, 已到期的定时器已被移出队列
+    pub fn remove(&mut self, key: &mut Option<usize>) {
+        if let Some(k) = key.take() {
+            if let Some(index) = self.entries.iter().position(|e| e.key == Some(k)) {
+                self.entries.remove(index);
+            }
+        }
+    }
+
+    /// 取出所有已到期的唤醒器, 由调用者在释放队列后唤醒
+    pub fn take_expired(&mut self, now: Instant) -> Vec<Waker> {
+        let count = self.entries.partition_point(|e| e.deadline <= now);
+        self.entries.drain(..count).map(|e| e.waker).collect()
+    }
+
+    /// 最近的到期时间
+    pub fn next_deadline(&self) -> Option<Instant> {
+        self.entries.first().map(|e| e.deadline)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+}
+
+/// 定时器登记, 丢弃时从定时器队列中注销
+///
+/// 提前结束的等待 (超时, select 等) 不会在队列中残留唤醒器
+pub(crate) struct TimerHandle {
+    key: Option<usize>,
+}
+
+impl TimerHandle {
+    pub const fn new() -> Self {
+        TimerHandle { key: None }
+    }
+
+    /// 登记或更新到期时间, 到达 deadline 时唤醒 waker
+    pub fn register(&mut self, deadline: Instant, waker: &Waker) {
+        Executor::register_timer(&mut self.key, deadline, waker);
+    }
+}
+
+impl Drop for TimerHandle {
+    fn drop(&mut self) {
+        if self.key.is_some() {
+            Executor::cancel_timer(&mut self.key);
+        }
+    }
+}
+
+/// 软件定时器回调, 在执行器上下文中调用
+pub type SoftTimerCallback = Box<dyn FnMut()>;
+
+struct SoftTimer {
+    id: u32,
+    deadline: Instant,
+    period: Option<Duration>,            // 周期定时器的周期, 单次定时器为 None
+    callback: Option<SoftTimerCallback>, // 回调执行期间被取出
+}
+
+/// 软件定时器列表, 按到期时间升序排列
+pub struct SoftTimers {
+    timers: Vec<SoftTimer>,
+    next_id: u32,
+}
+
+impl SoftTimers {
+    pub const fn new() -> Self {
+        SoftTimers {
+            timers: Vec::new(),
+            next_id: 0,
+        }
+    }
+
+    fn insert(&mut self, timer: SoftTimer) {
+        let index = self.timers.partition_point(|t| t.deadline <= timer.deadline);
+        self.timers.insert(index, timer);
+    }
+
+    /// 添加定时器, 返回定时器ID
+    pub fn add(
+        &mut self,
+        deadline: Instant,
+        period: Option<Duration>,
+        callback: SoftTimerCallback,
+    ) -> u32 {
+        let id = self.next_id;
+        self.next_id = self.next_id.wrapping_add(1);
+        self.insert(SoftTimer {
+            id,
+            deadline,
+            period,
+            callback: Some(callback),
+        });
+        id
+    }
+
+    /// 取消定时器, 返回定时器是否存在
+    pub fn cancel(&mut self, id: u32) -> bool {
+        let len = self.timers.len();
+        self.timers.retain(|t| t.id != id);
+        self.timers.len() != len
+    }
+
+    pub fn contains(&self, id: u32) -> bool {
+        self.timers.iter().any(|t| t.id == id)
+    }
+
+    /// 取出一个已到期的定时器的回调, 周期定时器按固定节拍重新排队, 错过的节拍被跳过
+    pub fn take_expired(&mut self, now: Instant) -> Option<(u32, SoftTimerCallback)> {
+        if self.timers.first().is_none_or(|t| t.deadline > now) {
+            return None;
+        }
+        let mut timer = self.timers.remove(0);
+        let id = timer.id;
+        let callback = timer.callback.take()?;
+        if let Some(period) = timer.period {
+            let mut deadline = timer.deadline + period;
+            if deadline <= now {
+                let period_ms = (period.as_millis() as u32).max(1);
+                let late_ms = now.duration_since(timer.deadline).as_millis() as u32;
+                deadline = now + Duration::from_millis((period_ms - late_ms % period_ms) as u64);
+            }
+            timer.deadline = deadline;
+            self.insert(timer);
+        }
+        Some((id, callback))
+    }
+
+    /// 周期定时器的回调执行完后放回, 定时器已在回调中被取消时丢弃
+    pub fn restore(&mut self, id: u32, callback: SoftTimerCallback) {
+        if let Some(timer) = self.timers.iter_mut().find(|t| t.id == id) {
+            timer.callback = Some(callback);
+        }
+    }
+
+    /// 最近的到期时间
+    pub fn next_deadline(&self) -> Option<Instant> {
+        self.timers.first().map(|t| t.deadline)
+    }
+}
@@ -9,6 +9,7 @@ pub use async_trait::async_trait;
 
 mod bindings;
 pub mod console;
+pub mod cron;
 pub mod driver;
 pub mod executor;
 pub mod sys;
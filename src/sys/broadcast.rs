@@ -0,0 +1,216 @@
+//! 广播通道
+//!
+//! 每个接收者的缓存使用容量为 N 的 VecDeque 而不是 util::RingBuf, 数据只需实现 Clone,
+//! 不受 RingBuf 的 `T: Copy + Default` 限制
+
+use alloc::collections::VecDeque;
+use alloc::rc::Rc;
+use alloc::vec::Vec;
+use core::cell::{Cell, RefCell};
+use core::future::Future;
+use core::pin::Pin;
+use core::task::{Context, Poll, Waker};
+
+/// 没有接收者, 携带未发送的数据
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct SendError<T>(pub T);
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum RecvError {
+    Closed,      // 所有发送者已关闭且数据已取完
+    Lagged(u32), // 接收过慢, 丢失了最旧的若干条数据
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum TryRecvError {
+    Empty,       // 没有新数据
+    Closed,      // 所有发送者已关闭且数据已取完
+    Lagged(u32), // 接收过慢, 丢失了最旧的若干条数据
+}
+
+// 每个接收者独立的缓存
+struct Slot<T> {
+    queue: VecDeque<T>, // 最多缓存 N 条数据
+    lagged: u32,
+    waker: Option<Waker>,
+}
+
+struct Shared<T, const N: usize> {
+    slots: RefCell<Vec<Option<Slot<T>>>>,
+    senders: Cell<usize>,
+}
+
+impl<T: Clone, const N: usize> Shared<T, N> {
+    fn subscribe(self: &Rc<Self>) -> Receiver<T, N> {
+        let slot = Slot {
+            queue: VecDeque::with_capacity(N),
+            lagged: 0,
+            waker: None,
+        };
+        let mut slots = self.slots.borrow_mut();
+        let index = match slots.iter().position(|s| s.is_none()) {
+            Some(index) => {
+                slots[index] = Some(slot);
+                index
+            }
+            None => {
+                slots.push(Some(slot));
+                slots.len() - 1
+            }
+        };
+        Receiver {
+            shared: self.clone(),
+            index,
+        }
+    }
+}
+
+/// 创建广播通道, 每条数据都会发送给所有接收者
+///
+/// 每个接收者有独立的缓存, 最多缓存 N 条数据, 发送不会挂起;
+/// 接收者的缓存满时丢弃最旧的数据, 并在下次接收时返回 Lagged
+pub fn channel<T: Clone, const N: usize>() -> (Sender<T, N>, Receiver<T, N>) {
+    assert!(N > 0, "CHANNEL SIZE ERR");
+    let shared = Rc::new(Shared {
+        slots: RefCell::new(Vec::new()),
+        senders: Cell::new(1),
+    });
+    let receiver = shared.subscribe();
+    (Sender { shared }, receiver)
+}
+
+pub struct Sender<T: Clone, const N: usize> {
+    shared: Rc<Shared<T, N>>,
+}
+
+impl<T: Clone, const N: usize> Sender<T, N> {
+    /// 发送数据给所有接收者, 返回接收者数量
+    pub fn send(&self, value: T) -> Result<usize, SendError<T>> {
+        let mut count = 0;
+        let mut wakers = Vec::new();
+        for slot in self.shared.slots.borrow_mut().iter_mut().flatten() {
+            if slot.queue.len() >= N {
+                slot.queue.pop_front();
+                slot.lagged = slot.lagged.saturating_add(1);
+            }
+            slot.queue.push_back(value.clone());
+            if let Some(waker) = slot.waker.take() {
+                wakers.push(waker);
+            }
+            count += 1;
+        }
+        for waker in wakers {
+            waker.wake();
+        }
+        if count == 0 {
+            Err(SendError(value))
+        } else {
+            Ok(count)
+        }
+    }
+
+    /// 创建新的接收者, 只接收之后发送的数据
+    pub fn subscribe(&self) -> Receiver<T, N> {
+        self.shared.subscribe()
+    }
+
+    pub fn receiver_count(&self) -> usize {
+        self.shared.slots.borrow().iter().flatten().count()
+    }
+}
+
+impl<T: Clone, const N: usize> Clone for Sender<T, N> {
+    fn clone(&self) -> Self {
+        self.shared.senders.set(self.shared.senders.get() + 1);
+        Sender {
+            shared: self.shared.clone(),
+        }
+    }
+}
+
+impl<T: Clone, const N: usize> Drop for Sender<T, N> {
+    fn drop(&mut self) {
+        self.shared.senders.set(self.shared.senders.get() - 1);
+        if self.shared.senders.get() == 0 {
+            // 最后一个发送者关闭, 唤醒所有接收者
+            let wakers: Vec<Waker> = self
+                .shared
+                .slots
+                .borrow_mut()
+                .iter_mut()
+                .flatten()
+                .filter_map(|slot| slot.waker.take())
+                .collect();
+            for waker in wakers {
+                waker.wake();
+            }
+        }
+    }
+}
+
+pub struct Receiver<T: Clone, const N: usize> {
+    shared: Rc<Shared<T, N>>,
+    index: usize,
+}
+
+impl<T: Clone, const N: usize> Receiver<T, N> {
+    /// 接收数据, 没有新数据时挂起
+    pub fn recv(&mut self) -> RecvFuture<'_, T, N> {
+        RecvFuture { receiver: self }
+    }
+
+    /// 尝试接收数据, 不挂起
+    pub fn try_recv(&mut self) -> Result<T, TryRecvError> {
+        let mut slots = self.shared.slots.borrow_mut();
+        let slot = match slots[self.index].as_mut() {
+            Some(slot) => slot,
+            None => return Err(TryRecvError::Closed),
+        };
+        if slot.lagged > 0 {
+            let lagged = slot.lagged;
+            slot.lagged = 0;
+            return Err(TryRecvError::Lagged(lagged));
+        }
+        match slot.queue.pop_front() {
+            Some(value) => Ok(value),
+            None if self.shared.senders.get() == 0 => Err(TryRecvError::Closed),
+            None => Err(TryRecvError::Empty),
+        }
+    }
+
+    /// 创建新的接收者, 只接收之后发送的数据
+    pub fn resubscribe(&self) -> Self {
+        self.shared.subscribe()
+    }
+}
+
+impl<T: Clone, const N: usize> Drop for Receiver<T, N> {
+    fn drop(&mut self) {
+        self.shared.slots.borrow_mut()[self.index] = None;
+    }
+}
+
+#[must_use = "futures do nothing unless you `.await` or poll them"]
+pub struct RecvFuture<'a, T: Clone, const N: usize> {
+    receiver: &'a mut Receiver<T, N>,
+}
+
+impl<T: Clone, const N: usize> Future for RecvFuture<'_, T, N> {
+    type Output = Result<T, RecvError>;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let this = self.get_mut();
+        match this.receiver.try_recv() {
+            Ok(value) => Poll::Ready(Ok(value)),
+            Err(TryRecvError::Closed) => Poll::Ready(Err(RecvError::Closed)),
+            Err(TryRecvError::Lagged(n)) => Poll::Ready(Err(RecvError::Lagged(n))),
+            Err(TryRecvError::Empty) => {
+                let index = this.receiver.index;
+                if let Some(slot) = this.receiver.shared.slots.borrow_mut()[index].as_mut() {
+                    slot.waker = Some(cx.waker().clone());
+                }
+                Poll::Pending
+            }
+        }
+    }
+}
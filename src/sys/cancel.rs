@@ -0,0 +1,34 @@
+use alloc::rc::Rc;
+
+use crate::sys::{Event, EventWaitFuture};
+
+/// 协作式取消令牌, 克隆的令牌共享同一个取消状态
+///
+/// 任务通过 `Executor::cancellation_token()` 获取自身的令牌后, 收到 SIGINT/SIGTERM
+/// 时不会被立即释放, 而是令牌被取消, 任务可在宽限期内完成清理后自行返回
+#[derive(Clone, Default)]
+pub struct CancellationToken {
+    event: Rc<Event>,
+}
+
+impl CancellationToken {
+    pub fn new() -> Self {
+        CancellationToken {
+            event: Rc::new(Event::new()),
+        }
+    }
+
+    /// 取消令牌, 唤醒所有等待取消的任务
+    pub fn cancel(&self) {
+        self.event.set();
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.event.is_set()
+    }
+
+    /// 等待令牌被取消
+    pub fn cancelled(&self) -> EventWaitFuture<'_> {
+        self.event.wait()
+    }
+}
@@ -0,0 +1,79 @@
+use core::cell::{Cell, RefCell};
+use core::future::Future;
+use core::pin::Pin;
+use core::task::{Context, Poll};
+
+use crate::sys::wait_list::WaitList;
+
+/// 手动复位事件, 置位后唤醒所有等待者, 直到调用 reset() 前 wait() 都立即返回
+pub struct Event {
+    set: Cell<bool>,
+    waiters: RefCell<WaitList>,
+}
+
+impl Event {
+    pub const fn new() -> Self {
+        Event {
+            set: Cell::new(false),
+            waiters: RefCell::new(WaitList::new()),
+        }
+    }
+
+    /// 置位事件并唤醒所有等待者
+    pub fn set(&self) {
+        self.set.set(true);
+        self.waiters.borrow_mut().notify_all();
+    }
+
+    /// 复位事件
+    pub fn reset(&self) {
+        self.set.set(false);
+    }
+
+    pub fn is_set(&self) -> bool {
+        self.set.get()
+    }
+
+    /// 等待事件被置位
+    pub fn wait(&self) -> EventWaitFuture<'_> {
+        EventWaitFuture {
+            event: self,
+            key: None,
+        }
+    }
+}
+
+impl Default for Event {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[must_use = "futures do nothing unless you `.await` or poll them"]
+pub struct EventWaitFuture<'a> {
+    event: &'a Event,
+    key: Option<usize>,
+}
+
+impl Future for EventWaitFuture<'_> {
+    type Output = ();
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let this = self.get_mut();
+        let mut waiters = this.event.waiters.borrow_mut();
+        // 被 set() 唤醒过即返回, 即使之后又被复位
+        if waiters.take_notified(&mut this.key) || this.event.set.get() {
+            waiters.remove(&mut this.key);
+            Poll::Ready(())
+        } else {
+            waiters.register(&mut this.key, cx.waker());
+            Poll::Pending
+        }
+    }
+}
+
+impl Drop for EventWaitFuture<'_> {
+    fn drop(&mut self) {
+        self.event.waiters.borrow_mut().remove(&mut self.key);
+    }
+}
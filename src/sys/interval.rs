@@ -0,0 +1,94 @@
+use core::future::poll_fn;
+use core::task::{Context, Poll};
+
+use crate::executor::TimerHandle;
+use crate::sys::{Duration, Instant};
+
+/// 错过节拍 (任务处理时间超过周期) 时的处理方式
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum MissedTickBehavior {
+    /// 立即连续触发错过的节拍, 直到追上原有节拍, 保证触发次数
+    #[default]
+    Burst,
+    /// 跳过错过的节拍, 下次在原有节拍上触发
+    Skip,
+    /// 从当前时间起重新计算节拍
+    Delay,
+}
+
+/// 周期定时器, 按固定节拍触发, 不会因任务的处理时间产生累计误差
+pub struct Interval {
+    next: Instant,
+    period: Duration,
+    missed_tick_behavior: MissedTickBehavior,
+    timer: TimerHandle, // 丢弃时注销定时器
+}
+
+impl Interval {
+    /// 等待下一个节拍, 返回该节拍的计划时间
+    pub async fn tick(&mut self) -> Instant {
+        poll_fn(|cx| self.poll_tick(cx)).await
+    }
+
+    pub fn poll_tick(&mut self, cx: &mut Context<'_>) -> Poll<Instant> {
+        let now = Instant::now();
+        if now < self.next {
+            self.timer.register(self.next, cx.waker());
+            return Poll::Pending;
+        }
+        let tick = self.next;
+        self.next = match self.missed_tick_behavior {
+            MissedTickBehavior::Burst => tick + self.period,
+            MissedTickBehavior::Skip => {
+                let period_ms = self.period.as_millis() as u32;
+                let late_ms = now.duration_since(tick).as_millis() as u32;
+                now + Duration::from_millis((period_ms - late_ms % period_ms) as u64)
+            }
+            MissedTickBehavior::Delay => now + self.period,
+        };
+        Poll::Ready(tick)
+    }
+
+    /// 从当前时间起重新开始计时, 下一个节拍在一个周期后
+    pub fn reset(&mut self) {
+        self.next = Instant::now() + self.period;
+    }
+
+    pub fn period(&self) -> Duration {
+        self.period
+    }
+
+    pub fn missed_tick_behavior(&self) -> MissedTickBehavior {
+        self.missed_tick_behavior
+    }
+
+    pub fn set_missed_tick_behavior(&mut self, behavior: MissedTickBehavior) {
+        self.missed_tick_behavior = behavior;
+    }
+}
+
+/// 创建周期为 period_ms 的定时器, 第一个节拍立即触发
+///
+/// ```ignore
+/// let mut interval = sys::interval(100);
+/// loop {
+///     interval.tick().await;
+///     sample();
+/// }
+/// ```
+#[allow(unused)]
+pub fn interval(period_ms: u32) -> Interval {
+    interval_at(Instant::now(), period_ms)
+}
+
+/// 创建周期为 period_ms 的定时器, 第一个节拍在 start 触发
+#[allow(unused)]
+pub fn interval_at(start: Instant, period_ms: u32) -> Interval {
+    assert!(period_ms > 0, "interval period must be non-zero");
+    Interval {
+        next: start,
+        period: Duration::from_millis(period_ms as u64),
+        missed_tick_behavior: MissedTickBehavior::default(),
+        timer: TimerHandle::new(),
+    }
+}
@@ -0,0 +1,129 @@
+use core::cell::UnsafeCell;
+use core::future::Future;
+use core::pin::Pin;
+use core::sync::atomic::{AtomicBool, Ordering};
+use core::task::{Context, Poll, Waker};
+
+use crate::sys::SimpleOs;
+
+/// 在临界区内执行闭包, 临界区由 `CpuDriver::cpu_enter_critical` 提供, 支持嵌套
+///
+/// 系统未初始化时直接执行
+pub fn critical_section<R>(f: impl FnOnce() -> R) -> R {
+    if !SimpleOs::is_initialized() {
+        return f();
+    }
+    let state = SimpleOs::cpu().cpu_enter_critical();
+    let result = f();
+    SimpleOs::cpu().cpu_exit_critical(state);
+    result
+}
+
+/// 可在中断中使用的唤醒器槽位
+///
+/// 任务通过 register() 登记唤醒器, 中断通过 wake() 唤醒该任务
+pub struct AtomicWaker {
+    waker: UnsafeCell<Option<Waker>>,
+}
+
+unsafe impl Sync for AtomicWaker {}
+
+impl AtomicWaker {
+    pub const fn new() -> Self {
+        AtomicWaker {
+            waker: UnsafeCell::new(None),
+        }
+    }
+
+    /// 登记唤醒器, 替换之前登记的唤醒器
+    pub fn register(&self, waker: &Waker) {
+        critical_section(|| {
+            let slot = unsafe { &mut *self.waker.get() };
+            if !slot.as_ref().is_some_and(|w| w.will_wake(waker)) {
+                *slot = Some(waker.clone());
+            }
+        });
+    }
+
+    /// 唤醒已登记的任务, 可在中断中调用
+    pub fn wake(&self) {
+        let waker = critical_section(|| unsafe { (*self.waker.get()).take() });
+        if let Some(waker) = waker {
+            waker.wake();
+        }
+    }
+}
+
+impl Default for AtomicWaker {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// 中断到任务的信号, 中断调用 signal(), 任务 await wait()
+///
+/// 多次 signal() 在任务处理前只会合并为一次
+pub struct IsrSignal {
+    signaled: AtomicBool,
+    waker: AtomicWaker,
+}
+
+impl IsrSignal {
+    pub const fn new() -> Self {
+        IsrSignal {
+            signaled: AtomicBool::new(false),
+            waker: AtomicWaker::new(),
+        }
+    }
+
+    /// 发出信号并唤醒等待的任务, 可在中断中调用
+    pub fn signal(&self) {
+        self.signaled.store(true, Ordering::Release);
+        self.waker.wake();
+    }
+
+    /// 清除未处理的信号
+    pub fn reset(&self) {
+        self.signaled.store(false, Ordering::Release);
+    }
+
+    pub fn is_signaled(&self) -> bool {
+        self.signaled.load(Ordering::Acquire)
+    }
+
+    /// 等待信号, 返回时清除信号
+    pub fn wait(&self) -> IsrSignalFuture<'_> {
+        IsrSignalFuture { signal: self }
+    }
+}
+
+impl Default for IsrSignal {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[must_use = "futures do nothing unless you `.await` or poll them"]
+pub struct IsrSignalFuture<'a> {
+    signal: &'a IsrSignal,
+}
+
+impl Future for IsrSignalFuture<'_> {
+    type Output = ();
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        // 先登记再检查, 避免中断在两者之间发生时丢失唤醒
+        self.signal.waker.register(cx.waker());
+        // 部分内核不支持原子交换指令, 在临界区内完成读取和清除
+        let signaled = critical_section(|| {
+            let signaled = self.signal.signaled.load(Ordering::Acquire);
+            self.signal.signaled.store(false, Ordering::Release);
+            signaled
+        });
+        if signaled {
+            Poll::Ready(())
+        } else {
+            Poll::Pending
+        }
+    }
+}
@@ -1,122 +1,219 @@
+//! 并发等待多个 future 全部完成
+//!
+//! 不兼容的变更: Join2 不再将子 future 装箱, 只有两个子 future 都是 Unpin 时 Join2 才是 Unpin;
+//! 依赖 Join2 总是 Unpin 的调用方 (例如在循环中轮询 `&mut` 引用) 需先用 `core::pin::pin!`
+//! 或 `Box::pin` 固定. `Join2::new` 保留, 与 `join` 相同
+
 use core::future::Future;
+use core::mem;
 use core::pin::Pin;
 use core::task::{Context, Poll};
 
 use alloc::boxed::Box;
+use alloc::vec::Vec;
 
-#[allow(unused)]
-#[must_use = "futures do nothing unless you `.await` or poll them"]
-pub struct Join2<F1, F2>
-where
-    F1: Future,
-    F2: Future,
-{
-    future1: Pin<Box<F1>>,
-    future2: Pin<Box<F2>>,
-    future1_done: bool,
-    future2_done: bool,
-    output1: Option<F1::Output>,
-    output2: Option<F2::Output>,
+// 保存子 future 或其结果, 子 future 原地固定, 不需要分配内存
+pub(crate) enum MaybeDone<F: Future> {
+    Future(F),
+    Done(F::Output),
+    Gone,
 }
 
-impl<F1, F2> Join2<F1, F2>
-where
-    F1: Future,
-    F2: Future,
-{
-    pub fn new(future1: F1, future2: F2) -> Self {
-        Self {
-            future1: Box::pin(future1),
-            future2: Box::pin(future2),
-            future1_done: false,
-            future2_done: false,
-            output1: None,
-            output2: None,
+impl<F: Future> MaybeDone<F> {
+    // 轮询未完成的子 future, 返回是否已完成
+    fn poll_done(self: Pin<&mut Self>, cx: &mut Context<'_>) -> bool {
+        // 子 future 只在原地被轮询和释放, 不会被移动
+        let this = unsafe { self.get_unchecked_mut() };
+        if let MaybeDone::Future(future) = this {
+            match unsafe { Pin::new_unchecked(future) }.poll(cx) {
+                Poll::Ready(output) => *this = MaybeDone::Done(output),
+                Poll::Pending => return false,
+            }
         }
+        true
     }
-}
 
-impl<F1, F2> Future for Join2<F1, F2>
-where
-    F1: Future,
-    F2: Future,
-{
-    type Output = (F1::Output, F2::Output);
+    // 取出结果, 只能在完成后调用一次
+    fn take_output(self: Pin<&mut Self>) -> Option<F::Output> {
+        // 完成后不再包含子 future, 可以移动
+        let this = unsafe { self.get_unchecked_mut() };
+        match this {
+            MaybeDone::Done(_) => match mem::replace(this, MaybeDone::Gone) {
+                MaybeDone::Done(output) => Some(output),
+                _ => None,
+            },
+            _ => None,
+        }
+    }
+}
 
-    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+impl<T, E, F: Future<Output = Result<T, E>>> MaybeDone<F> {
+    // 子 future 返回错误时取出错误
+    fn take_err(self: Pin<&mut Self>) -> Option<E> {
         let this = unsafe { self.get_unchecked_mut() };
+        match this {
+            MaybeDone::Done(Err(_)) => match mem::replace(this, MaybeDone::Gone) {
+                MaybeDone::Done(Err(err)) => Some(err),
+                _ => None,
+            },
+            _ => None,
+        }
+    }
+}
 
-        if !this.future1_done {
-            match this.future1.as_mut().poll(cx) {
-                Poll::Ready(output) => {
-                    this.output1 = Some(output);
-                    this.future1_done = true;
+// 生成 JoinN/TryJoinN 及对应的构造函数
+macro_rules! impl_join {
+    ($Join:ident, $join:ident, $TryJoin:ident, $try_join:ident; $($F:ident $T:ident $f:ident),+) => {
+        /// 并发等待所有 future 完成, 子 future 原地固定, 不分配内存
+        #[must_use = "futures do nothing unless you `.await` or poll them"]
+        pub struct $Join<$($F: Future),+> {
+            $($f: MaybeDone<$F>,)+
+        }
+
+        impl<$($F: Future),+> Future for $Join<$($F),+> {
+            type Output = ($($F::Output,)+);
+
+            fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+                let this = unsafe { self.get_unchecked_mut() };
+                let mut done = true;
+                $(
+                    done &= unsafe { Pin::new_unchecked(&mut this.$f) }.poll_done(cx);
+                )+
+                if done {
+                    Poll::Ready(($(
+                        unsafe { Pin::new_unchecked(&mut this.$f) }.take_output().unwrap(),
+                    )+))
+                } else {
+                    // 子 future 已注册唤醒器, 无需主动唤醒
+                    Poll::Pending
                 }
-                Poll::Pending => {}
             }
         }
 
-        if !this.future2_done {
-            match this.future2.as_mut().poll(cx) {
-                Poll::Ready(output) => {
-                    this.output2 = Some(output);
-                    this.future2_done = true;
+        impl<$($F: Future),+> $Join<$($F),+> {
+            pub fn new($($f: $F),+) -> Self {
+                $Join {
+                    $($f: MaybeDone::Future($f),)+
+                }
+            }
+        }
+
+        pub fn $join<$($F: Future),+>($($f: $F),+) -> $Join<$($F),+> {
+            $Join::new($($f),+)
+        }
+
+        /// 并发等待所有返回 Result 的 future, 任意一个返回错误时立即返回该错误
+        #[must_use = "futures do nothing unless you `.await` or poll them"]
+        pub struct $TryJoin<$($F: Future),+> {
+            $($f: MaybeDone<$F>,)+
+        }
+
+        impl<E, $($T, $F: Future<Output = Result<$T, E>>),+> Future for $TryJoin<$($F),+> {
+            type Output = Result<($($T,)+), E>;
+
+            fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+                let this = unsafe { self.get_unchecked_mut() };
+                let mut done = true;
+                $(
+                    let mut future = unsafe { Pin::new_unchecked(&mut this.$f) };
+                    done &= future.as_mut().poll_done(cx);
+                    if let Some(err) = future.take_err() {
+                        return Poll::Ready(Err(err));
+                    }
+                )+
+                if done {
+                    Poll::Ready(Ok(($(
+                        match unsafe { Pin::new_unchecked(&mut this.$f) }.take_output() {
+                            Some(Ok(output)) => output,
+                            _ => unreachable!(),
+                        },
+                    )+)))
+                } else {
+                    Poll::Pending
                 }
-                Poll::Pending => {}
             }
         }
 
-        if this.future1_done && this.future2_done {
-            let output1 = this.output1.take().unwrap();
-            let output2 = this.output2.take().unwrap();
-            Poll::Ready((output1, output2))
+        pub fn $try_join<E, $($T, $F: Future<Output = Result<$T, E>>),+>($($f: $F),+) -> $TryJoin<$($F),+> {
+            $TryJoin {
+                $($f: MaybeDone::Future($f),)+
+            }
+        }
+    };
+}
+
+impl_join!(Join2, join, TryJoin2, try_join; F1 T1 future1, F2 T2 future2);
+impl_join!(Join3, join3, TryJoin3, try_join3; F1 T1 future1, F2 T2 future2, F3 T3 future3);
+impl_join!(Join4, join4, TryJoin4, try_join4; F1 T1 future1, F2 T2 future2, F3 T3 future3, F4 T4 future4);
+impl_join!(Join5, join5, TryJoin5, try_join5; F1 T1 future1, F2 T2 future2, F3 T3 future3, F4 T4 future4, F5 T5 future5);
+
+/// 并发等待数量不定的 future 全部完成, 按顺序返回结果
+///
+/// 所有子 future 保存在一次分配的数组中
+#[must_use = "futures do nothing unless you `.await` or poll them"]
+pub struct JoinAll<F: Future> {
+    futures: Pin<Box<[MaybeDone<F>]>>,
+}
+
+impl<F: Future> Future for JoinAll<F> {
+    type Output = Vec<F::Output>;
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let futures = unsafe { self.futures.as_mut().get_unchecked_mut() };
+        let mut done = true;
+        for future in futures.iter_mut() {
+            done &= unsafe { Pin::new_unchecked(future) }.poll_done(cx);
+        }
+        if done {
+            Poll::Ready(
+                futures
+                    .iter_mut()
+                    .map(|future| unsafe { Pin::new_unchecked(future) }.take_output().unwrap())
+                    .collect(),
+            )
         } else {
-            cx.waker().wake_by_ref();
             Poll::Pending
         }
     }
 }
 
-impl<F1, F2> Unpin for Join2<F1, F2>
-where
-    F1: Future,
-    F2: Future,
-{
+pub fn join_all<F: Future>(futures: impl IntoIterator<Item = F>) -> JoinAll<F> {
+    let futures: Box<[MaybeDone<F>]> = futures.into_iter().map(MaybeDone::Future).collect();
+    JoinAll {
+        futures: Box::into_pin(futures),
+    }
 }
 
-#[must_use = "futures do nothing unless you `.await` or poll them"]
-pub fn join<F1, F2>(future1: F1, future2: F2) -> Join2<F1, F2>
-where
-    F1: Future,
-    F2: Future,
-{
-    Join2::new(future1, future2)
+/// 并发等待多个 future, 自动包含 .await, 最多支持 5 个
+#[macro_export]
+macro_rules! join {
+    ($fut1:expr, $fut2:expr $(,)?) => {
+        $crate::sys::join($fut1, $fut2).await
+    };
+    ($fut1:expr, $fut2:expr, $fut3:expr $(,)?) => {
+        $crate::sys::join3($fut1, $fut2, $fut3).await
+    };
+    ($fut1:expr, $fut2:expr, $fut3:expr, $fut4:expr $(,)?) => {
+        $crate::sys::join4($fut1, $fut2, $fut3, $fut4).await
+    };
+    ($fut1:expr, $fut2:expr, $fut3:expr, $fut4:expr, $fut5:expr $(,)?) => {
+        $crate::sys::join5($fut1, $fut2, $fut3, $fut4, $fut5).await
+    };
 }
 
-// 改进版宏,自动包含 .await
+/// 并发等待多个返回 Result 的 future, 自动包含 .await, 最多支持 5 个
 #[macro_export]
-macro_rules! join {
-    ($fut1:expr, $fut2:expr) => {
-        async {
-            $crate::sys::join($fut1, $fut2).await
-        }.await
+macro_rules! try_join {
+    ($fut1:expr, $fut2:expr $(,)?) => {
+        $crate::sys::try_join($fut1, $fut2).await
     };
-    ($fut1:expr, $fut2:expr, $fut3:expr) => {
-        async {
-            let ((r1, r2), r3) = $crate::sys::join(
-                $crate::sys::join($fut1, $fut2),
-                $fut3
-            ).await;
-            (r1, r2, r3)
-        }.await
+    ($fut1:expr, $fut2:expr, $fut3:expr $(,)?) => {
+        $crate::sys::try_join3($fut1, $fut2, $fut3).await
     };
-    ($fut1:expr, $fut2:expr, $fut3:expr, $fut4:expr) => {
-        async {
-            let ((r1, r2), (r3, r4)) = $crate::sys::join(
-                $crate::sys::join($fut1, $fut2),
-                $crate::sys::join($fut3, $fut4)
-            ).await;
-            (r1, r2, r3, r4)
-        }.await
+    ($fut1:expr, $fut2:expr, $fut3:expr, $fut4:expr $(,)?) => {
+        $crate::sys::try_join4($fut1, $fut2, $fut3, $fut4).await
     };
-}
\ No newline at end of file
+    ($fut1:expr, $fut2:expr, $fut3:expr, $fut4:expr, $fut5:expr $(,)?) => {
+        $crate::sys::try_join5($fut1, $fut2, $fut3, $fut4, $fut5).await
+    };
+}
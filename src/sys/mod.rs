@@ -1,11 +1,16 @@
 use crate::{
-    driver::cpu::CpuDriver, driver::systick::SysTickDriver, driver::tty::TtyDriver, singleton,
+    driver::cpu::CpuDriver, driver::systick::SysTickDriver, driver::tty::TtyDriver,
+    driver::watchdog::WatchdogDriver, singleton,
 };
 
 pub trait Device {
     fn get_cpu(&self) -> &'static mut dyn CpuDriver;
     fn get_tty(&self) -> &'static mut dyn TtyDriver;
     fn get_systick(&self) -> &'static mut dyn SysTickDriver;
+    /// 硬件看门狗, 没有时返回 None
+    fn get_watchdog(&self) -> Option<&'static mut dyn WatchdogDriver> {
+        None
+    }
 }
 
 pub struct SimpleOs {
@@ -36,17 +41,48 @@ impl SimpleOs {
     pub fn systick() -> &'static mut dyn SysTickDriver {
         SimpleOs::device().get_systick()
     }
+    pub fn watchdog() -> Option<&'static mut dyn WatchdogDriver> {
+        SimpleOs::device().get_watchdog()
+    }
 }
 
+mod cancel;
+mod event;
+mod interval;
+mod isr;
 mod join;
+mod mutex;
+mod notify;
 mod print;
 mod select;
+mod semaphore;
 mod sleep;
+mod soft_timer;
+mod stdio;
+mod time;
+mod timeout;
+mod wait_list;
 mod yield_now;
 
+pub mod broadcast;
+pub mod mpsc;
+pub mod oneshot;
+pub mod watch;
+
+pub use cancel::*;
+pub use event::*;
+pub use interval::*;
+pub use isr::*;
 pub use join::*;
+pub use mutex::*;
+pub use notify::*;
 pub use select::*;
+pub use semaphore::*;
 pub use sleep::*;
+pub use soft_timer::*;
+pub use stdio::*;
+pub use time::*;
+pub use timeout::*;
 pub use yield_now::*;
 
 pub use crate::print;
@@ -0,0 +1,251 @@
+//! 有界多生产者单消费者通道
+//!
+//! 缓存使用容量为 N 的 VecDeque 而不是 util::RingBuf: RingBuf 要求 `T: Copy + Default`,
+//! 无法传递 String, Box 等数据
+
+use alloc::collections::VecDeque;
+use alloc::rc::Rc;
+use core::cell::{Cell, RefCell};
+use core::future::Future;
+use core::pin::Pin;
+use core::task::{Context, Poll};
+
+use crate::sys::wait_list::WaitList;
+
+/// 接收者已关闭, 携带未发送的数据
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct SendError<T>(pub T);
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum TrySendError<T> {
+    Full(T),   // 通道已满
+    Closed(T), // 接收者已关闭
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum TryRecvError {
+    Empty,  // 通道为空
+    Closed, // 通道为空且所有发送者已关闭
+}
+
+struct Shared<T, const N: usize> {
+    buf: RefCell<VecDeque<T>>, // 最多缓存 N 条数据
+    senders: Cell<usize>,
+    receiver_closed: Cell<bool>,
+    send_waiters: RefCell<WaitList>,
+    recv_waiters: RefCell<WaitList>,
+}
+
+/// 创建有界多生产者单消费者通道
+///
+/// 最多缓存 N 条数据. 通道满时发送者挂起, 通道空时接收者挂起;
+/// 所有发送者或接收者被丢弃 (例如所在任务退出) 后通道关闭
+pub fn channel<T, const N: usize>() -> (Sender<T, N>, Receiver<T, N>) {
+    assert!(N > 0, "CHANNEL SIZE ERR");
+    let shared = Rc::new(Shared {
+        buf: RefCell::new(VecDeque::with_capacity(N)),
+        senders: Cell::new(1),
+        receiver_closed: Cell::new(false),
+        send_waiters: RefCell::new(WaitList::new()),
+        recv_waiters: RefCell::new(WaitList::new()),
+    });
+    (
+        Sender {
+            shared: shared.clone(),
+        },
+        Receiver { shared },
+    )
+}
+
+pub struct Sender<T, const N: usize> {
+    shared: Rc<Shared<T, N>>,
+}
+
+impl<T, const N: usize> Sender<T, N> {
+    /// 发送数据, 通道满时挂起, 接收者关闭时返回错误
+    pub fn send(&self, value: T) -> SendFuture<'_, T, N> {
+        SendFuture {
+            sender: self,
+            value: Some(value),
+            key: None,
+        }
+    }
+
+    /// 尝试发送数据, 不挂起
+    pub fn try_send(&self, value: T) -> Result<(), TrySendError<T>> {
+        if self.shared.receiver_closed.get() {
+            return Err(TrySendError::Closed(value));
+        }
+        {
+            let mut buf = self.shared.buf.borrow_mut();
+            if buf.len() >= N {
+                return Err(TrySendError::Full(value));
+            }
+            buf.push_back(value);
+        }
+        self.shared.recv_waiters.borrow_mut().notify_one();
+        Ok(())
+    }
+
+    /// 接收者是否已关闭
+    pub fn is_closed(&self) -> bool {
+        self.shared.receiver_closed.get()
+    }
+}
+
+impl<T, const N: usize> Clone for Sender<T, N> {
+    fn clone(&self) -> Self {
+        self.shared.senders.set(self.shared.senders.get() + 1);
+        Sender {
+            shared: self.shared.clone(),
+        }
+    }
+}
+
+impl<T, const N: usize> Drop for Sender<T, N> {
+    fn drop(&mut self) {
+        self.shared.senders.set(self.shared.senders.get() - 1);
+        if self.shared.senders.get() == 0 {
+            // 最后一个发送者关闭, 唤醒接收者
+            self.shared.recv_waiters.borrow_mut().notify_all();
+        }
+    }
+}
+
+#[must_use = "futures do nothing unless you `.await` or poll them"]
+pub struct SendFuture<'a, T, const N: usize> {
+    sender: &'a Sender<T, N>,
+    value: Option<T>,
+    key: Option<usize>,
+}
+
+impl<T, const N: usize> Future for SendFuture<'_, T, N> {
+    type Output = Result<(), SendError<T>>;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let this = unsafe { self.get_unchecked_mut() };
+        let shared = &this.sender.shared;
+        let value = match this.value.take() {
+            Some(value) => value,
+            None => panic!("SendFuture polled after completion"),
+        };
+        match this.sender.try_send(value) {
+            Ok(()) => {
+                shared.send_waiters.borrow_mut().remove(&mut this.key);
+                Poll::Ready(Ok(()))
+            }
+            Err(TrySendError::Closed(value)) => {
+                shared.send_waiters.borrow_mut().remove(&mut this.key);
+                Poll::Ready(Err(SendError(value)))
+            }
+            Err(TrySendError::Full(value)) => {
+                this.value = Some(value);
+                shared
+                    .send_waiters
+                    .borrow_mut()
+                    .register(&mut this.key, cx.waker());
+                Poll::Pending
+            }
+        }
+    }
+}
+
+impl<T, const N: usize> Drop for SendFuture<'_, T, N> {
+    fn drop(&mut self) {
+        let shared = &self.sender.shared;
+        let mut waiters = shared.send_waiters.borrow_mut();
+        // 被唤醒后取消发送, 将唤醒转交给下一个发送者
+        if waiters.remove(&mut self.key) && shared.buf.borrow().len() < N {
+            waiters.notify_one();
+        }
+    }
+}
+
+pub struct Receiver<T, const N: usize> {
+    shared: Rc<Shared<T, N>>,
+}
+
+impl<T, const N: usize> Receiver<T, N> {
+    /// 接收数据, 通道空时挂起, 所有发送者关闭且数据取完后返回 None
+    pub fn recv(&mut self) -> RecvFuture<'_, T, N> {
+        RecvFuture {
+            receiver: self,
+            key: None,
+        }
+    }
+
+    /// 尝试接收数据, 不挂起
+    pub fn try_recv(&mut self) -> Result<T, TryRecvError> {
+        let value = self.shared.buf.borrow_mut().pop_front();
+        match value {
+            Some(value) => {
+                self.shared.send_waiters.borrow_mut().notify_one();
+                Ok(value)
+            }
+            None if self.shared.senders.get() == 0 => Err(TryRecvError::Closed),
+            None => Err(TryRecvError::Empty),
+        }
+    }
+
+    /// 关闭接收端, 之后发送会失败, 已缓存的数据仍可接收
+    pub fn close(&mut self) {
+        self.shared.receiver_closed.set(true);
+        self.shared.send_waiters.borrow_mut().notify_all();
+    }
+
+    /// 缓存中的数据数量
+    pub fn len(&self) -> usize {
+        self.shared.buf.borrow().len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.shared.buf.borrow().is_empty()
+    }
+}
+
+impl<T, const N: usize> Drop for Receiver<T, N> {
+    fn drop(&mut self) {
+        self.close();
+    }
+}
+
+#[must_use = "futures do nothing unless you `.await` or poll them"]
+pub struct RecvFuture<'a, T, const N: usize> {
+    receiver: &'a mut Receiver<T, N>,
+    key: Option<usize>,
+}
+
+impl<T, const N: usize> Future for RecvFuture<'_, T, N> {
+    type Output = Option<T>;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let this = self.get_mut();
+        match this.receiver.try_recv() {
+            Ok(value) => {
+                let shared = &this.receiver.shared;
+                shared.recv_waiters.borrow_mut().remove(&mut this.key);
+                Poll::Ready(Some(value))
+            }
+            Err(TryRecvError::Closed) => {
+                let shared = &this.receiver.shared;
+                shared.recv_waiters.borrow_mut().remove(&mut this.key);
+                Poll::Ready(None)
+            }
+            Err(TryRecvError::Empty) => {
+                let shared = &this.receiver.shared;
+                shared
+                    .recv_waiters
+                    .borrow_mut()
+                    .register(&mut this.key, cx.waker());
+                Poll::Pending
+            }
+        }
+    }
+}
+
+impl<T, const N: usize> Drop for RecvFuture<'_, T, N> {
+    fn drop(&mut self) {
+        let shared = &self.receiver.shared;
+        shared.recv_waiters.borrow_mut().remove(&mut self.key);
+    }
+}
@@ -0,0 +1,122 @@
+use core::cell::{Cell, RefCell, UnsafeCell};
+use core::future::Future;
+use core::ops::{Deref, DerefMut};
+use core::pin::Pin;
+use core::task::{Context, Poll};
+
+use crate::sys::wait_list::WaitList;
+
+/// 异步互斥锁, 获取失败的任务会被挂起, 直到锁被释放时唤醒
+///
+/// 执行器为单线程, 该锁只用于任务之间, 不能在中断中使用
+pub struct Mutex<T: ?Sized> {
+    locked: Cell<bool>,
+    waiters: RefCell<WaitList>,
+    value: UnsafeCell<T>,
+}
+
+impl<T> Mutex<T> {
+    pub const fn new(value: T) -> Self {
+        Mutex {
+            locked: Cell::new(false),
+            waiters: RefCell::new(WaitList::new()),
+            value: UnsafeCell::new(value),
+        }
+    }
+
+    pub fn into_inner(self) -> T {
+        self.value.into_inner()
+    }
+}
+
+impl<T: ?Sized> Mutex<T> {
+    /// 获取锁, 锁被占用时挂起当前任务
+    pub fn lock(&self) -> MutexLockFuture<'_, T> {
+        MutexLockFuture {
+            mutex: self,
+            key: None,
+        }
+    }
+
+    /// 尝试获取锁, 锁被占用时立即返回 None
+    pub fn try_lock(&self) -> Option<MutexGuard<'_, T>> {
+        if self.locked.get() {
+            None
+        } else {
+            self.locked.set(true);
+            Some(MutexGuard { mutex: self })
+        }
+    }
+
+    pub fn is_locked(&self) -> bool {
+        self.locked.get()
+    }
+
+    /// 通过可变引用直接访问, 无需加锁
+    pub fn get_mut(&mut self) -> &mut T {
+        self.value.get_mut()
+    }
+
+    fn unlock(&self) {
+        self.locked.set(false);
+        self.waiters.borrow_mut().notify_one();
+    }
+}
+
+#[must_use = "futures do nothing unless you `.await` or poll them"]
+pub struct MutexLockFuture<'a, T: ?Sized> {
+    mutex: &'a Mutex<T>,
+    key: Option<usize>,
+}
+
+impl<'a, T: ?Sized> Future for MutexLockFuture<'a, T> {
+    type Output = MutexGuard<'a, T>;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let this = self.get_mut();
+        let mut waiters = this.mutex.waiters.borrow_mut();
+        if !this.mutex.locked.get() {
+            this.mutex.locked.set(true);
+            waiters.remove(&mut this.key);
+            Poll::Ready(MutexGuard { mutex: this.mutex })
+        } else {
+            waiters.register(&mut this.key, cx.waker());
+            Poll::Pending
+        }
+    }
+}
+
+impl<T: ?Sized> Drop for MutexLockFuture<'_, T> {
+    fn drop(&mut self) {
+        let mut waiters = self.mutex.waiters.borrow_mut();
+        // 被唤醒后取消等待, 将唤醒转交给下一个等待者
+        if waiters.remove(&mut self.key) && !self.mutex.locked.get() {
+            waiters.notify_one();
+        }
+    }
+}
+
+/// 互斥锁守卫, 离开作用域时释放锁
+pub struct MutexGuard<'a, T: ?Sized> {
+    mutex: &'a Mutex<T>,
+}
+
+impl<T: ?Sized> Deref for MutexGuard<'_, T> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        unsafe { &*self.mutex.value.get() }
+    }
+}
+
+impl<T: ?Sized> DerefMut for MutexGuard<'_, T> {
+    fn deref_mut(&mut self) -> &mut T {
+        unsafe { &mut *self.mutex.value.get() }
+    }
+}
+
+impl<T: ?Sized> Drop for MutexGuard<'_, T> {
+    fn drop(&mut self) {
+        self.mutex.unlock();
+    }
+}
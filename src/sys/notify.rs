@@ -0,0 +1,84 @@
+use core::cell::{Cell, RefCell};
+use core::future::Future;
+use core::pin::Pin;
+use core::task::{Context, Poll};
+
+use crate::sys::wait_list::WaitList;
+
+/// 任务通知, 用于一个任务唤醒其他等待中的任务
+///
+/// notify_one() 在没有等待者时会保存一次通知, 下一次 notified() 立即返回;
+/// notify_waiters() 只唤醒当前的等待者, 不保存通知
+pub struct Notify {
+    permit: Cell<bool>,
+    waiters: RefCell<WaitList>,
+}
+
+impl Notify {
+    pub const fn new() -> Self {
+        Notify {
+            permit: Cell::new(false),
+            waiters: RefCell::new(WaitList::new()),
+        }
+    }
+
+    /// 唤醒最早的一个等待者, 没有等待者时保存通知
+    pub fn notify_one(&self) {
+        if !self.waiters.borrow_mut().notify_one() {
+            self.permit.set(true);
+        }
+    }
+
+    /// 唤醒当前所有等待者
+    pub fn notify_waiters(&self) {
+        self.waiters.borrow_mut().notify_all();
+    }
+
+    /// 等待通知
+    pub fn notified(&self) -> NotifiedFuture<'_> {
+        NotifiedFuture {
+            notify: self,
+            key: None,
+        }
+    }
+}
+
+impl Default for Notify {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[must_use = "futures do nothing unless you `.await` or poll them"]
+pub struct NotifiedFuture<'a> {
+    notify: &'a Notify,
+    key: Option<usize>,
+}
+
+impl Future for NotifiedFuture<'_> {
+    type Output = ();
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let this = self.get_mut();
+        let mut waiters = this.notify.waiters.borrow_mut();
+        if waiters.take_notified(&mut this.key) {
+            return Poll::Ready(());
+        }
+        if this.key.is_none() && this.notify.permit.get() {
+            this.notify.permit.set(false);
+            return Poll::Ready(());
+        }
+        waiters.register(&mut this.key, cx.waker());
+        Poll::Pending
+    }
+}
+
+impl Drop for NotifiedFuture<'_> {
+    fn drop(&mut self) {
+        let baton = self.notify.waiters.borrow_mut().remove(&mut self.key);
+        // 被 notify_one() 唤醒后取消等待, 将通知转交出去
+        if baton {
+            self.notify.notify_one();
+        }
+    }
+}
@@ -0,0 +1,119 @@
+use alloc::rc::Rc;
+use core::cell::{Cell, RefCell};
+use core::future::Future;
+use core::pin::Pin;
+use core::task::{Context, Poll, Waker};
+
+/// 发送者在发送前被丢弃
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct RecvError;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum TryRecvError {
+    Empty,  // 尚未发送
+    Closed, // 发送者已关闭且没有数据
+}
+
+struct Shared<T> {
+    value: RefCell<Option<T>>,
+    sender_closed: Cell<bool>,
+    receiver_closed: Cell<bool>,
+    receiver_waker: RefCell<Option<Waker>>,
+}
+
+/// 创建一次性通道, 只能发送一个值
+///
+/// 发送者被丢弃 (例如所在任务退出) 而未发送时, 接收者得到 RecvError
+pub fn channel<T>() -> (Sender<T>, Receiver<T>) {
+    let shared = Rc::new(Shared {
+        value: RefCell::new(None),
+        sender_closed: Cell::new(false),
+        receiver_closed: Cell::new(false),
+        receiver_waker: RefCell::new(None),
+    });
+    (
+        Sender {
+            shared: shared.clone(),
+        },
+        Receiver { shared },
+    )
+}
+
+pub struct Sender<T> {
+    shared: Rc<Shared<T>>,
+}
+
+impl<T> Sender<T> {
+    /// 发送数据, 接收者已关闭时返回原数据
+    pub fn send(self, value: T) -> Result<(), T> {
+        if self.shared.receiver_closed.get() {
+            return Err(value);
+        }
+        *self.shared.value.borrow_mut() = Some(value);
+        Ok(())
+        // self 在此处被丢弃, 由 Drop 唤醒接收者
+    }
+
+    /// 接收者是否已关闭
+    pub fn is_closed(&self) -> bool {
+        self.shared.receiver_closed.get()
+    }
+}
+
+impl<T> Drop for Sender<T> {
+    fn drop(&mut self) {
+        self.shared.sender_closed.set(true);
+        if let Some(waker) = self.shared.receiver_waker.borrow_mut().take() {
+            waker.wake();
+        }
+    }
+}
+
+/// 接收者本身是一个 future, await 得到发送的值
+pub struct Receiver<T> {
+    shared: Rc<Shared<T>>,
+}
+
+impl<T> Receiver<T> {
+    /// 尝试接收数据, 不挂起
+    pub fn try_recv(&mut self) -> Result<T, TryRecvError> {
+        if let Some(value) = self.shared.value.borrow_mut().take() {
+            return Ok(value);
+        }
+        if self.shared.sender_closed.get() {
+            Err(TryRecvError::Closed)
+        } else {
+            Err(TryRecvError::Empty)
+        }
+    }
+
+    /// 关闭接收端, 之后发送会失败
+    pub fn close(&mut self) {
+        self.shared.receiver_closed.set(true);
+    }
+}
+
+impl<T> Future for Receiver<T> {
+    type Output = Result<T, RecvError>;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let this = self.get_mut();
+        match this.try_recv() {
+            Ok(value) => Poll::Ready(Ok(value)),
+            Err(TryRecvError::Closed) => Poll::Ready(Err(RecvError)),
+            Err(TryRecvError::Empty) => {
+                let mut waker = this.shared.receiver_waker.borrow_mut();
+                if !waker.as_ref().is_some_and(|w| w.will_wake(cx.waker())) {
+                    *waker = Some(cx.waker().clone());
+                }
+                Poll::Pending
+            }
+        }
+    }
+}
+
+impl<T> Drop for Receiver<T> {
+    fn drop(&mut self) {
+        self.close();
+    }
+}
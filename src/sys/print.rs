@@ -1,33 +1,27 @@
+/// 打印到当前任务的标准输出, 未重定向时打印到终端
 #[macro_export]
 macro_rules! print {
     () => {{
-        if $crate::sys::SimpleOs::is_initialized() {
-            $crate::sys::SimpleOs::tty().tty_flush();
-        }
+        $crate::sys::print_flush();
     }};
     ($($arg:tt)*) => {{
-        if $crate::sys::SimpleOs::is_initialized() {
-            let formatted = alloc::format!($($arg)*);
-            $crate::sys::SimpleOs::tty().tty_write(formatted.as_bytes());
-            $crate::sys::SimpleOs::tty().tty_flush();
-        }
+        let formatted = alloc::format!($($arg)*);
+        $crate::sys::stdout_write(formatted.as_bytes());
+        $crate::sys::print_flush();
     }};
 }
 
+/// 打印一行到当前任务的标准输出, 未重定向时打印到终端
 #[macro_export]
 macro_rules! println {
     () => {{
-        if $crate::sys::SimpleOs::is_initialized() {
-            $crate::sys::SimpleOs::tty().tty_write(b"\r\n");
-            $crate::sys::SimpleOs::tty().tty_flush();
-        }
+        $crate::sys::stdout_write(b"\n");
+        $crate::sys::print_flush();
     }};
     ($($arg:tt)*) => {{
-        if $crate::sys::SimpleOs::is_initialized() {
-            let formatted = alloc::format!($($arg)*);
-            $crate::sys::SimpleOs::tty().tty_write(formatted.as_bytes());
-            $crate::sys::SimpleOs::tty().tty_write(b"\r\n");
-            $crate::sys::SimpleOs::tty().tty_flush();
-        }
+        let formatted = alloc::format!($($arg)*);
+        $crate::sys::stdout_write(formatted.as_bytes());
+        $crate::sys::stdout_write(b"\n");
+        $crate::sys::print_flush();
     }};
 }
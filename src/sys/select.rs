@@ -1,103 +1,150 @@
+//! 等待多个 future 中任意一个完成
+//!
+//! 不兼容的变更: Select2 不再将子 future 装箱, 只有两个子 future 都是 Unpin 时 Select2 才是 Unpin;
+//! 依赖 Select2 总是 Unpin 的调用方 (例如在循环中轮询 `&mut` 引用) 需先用 `core::pin::pin!`
+//! 或 `Box::pin` 固定. `Select2::new` 保留, 与 `select` 相同
+
 use core::future::Future;
 use core::pin::Pin;
 use core::task::{Context, Poll};
-use alloc::boxed::Box;
 
-pub struct Select2<F1, F2>
-where
-    F1: Future,
-    F2: Future,
-{
-    future1: Pin<Box<F1>>,
-    future2: Pin<Box<F2>>,
-    future1_done: bool,
-    future2_done: bool,
-}
+use alloc::boxed::Box;
 
-impl<F1, F2> Select2<F1, F2>
-where
-    F1: Future,
-    F2: Future,
-{
-    pub fn new(future1: F1, future2: F2) -> Self {
-        Self {
-            future1: Box::pin(future1),
-            future2: Box::pin(future2),
-            future1_done: false,
-            future2_done: false,
+// 生成 SelectN 及对应的构造函数和结果类型
+macro_rules! impl_select {
+    ($Select:ident, $select:ident, $Output:ident; $($F:ident $T:ident $f:ident $Variant:ident),+) => {
+        /// 等待任意一个 future 完成, 按参数顺序轮询, 子 future 原地固定, 不分配内存
+        ///
+        /// 返回后其余的 future 随 Select 一起被释放
+        #[must_use = "futures do nothing unless you `.await` or poll them"]
+        pub struct $Select<$($F: Future),+> {
+            $($f: $F,)+
         }
-    }
-}
-
-pub enum Select2Output<T1, T2> {
-    Future1(T1),
-    Future2(T2),
-}
 
-impl<F1, F2> Future for Select2<F1, F2>
-where
-    F1: Future,
-    F2: Future,
-{
-    type Output = Select2Output<F1::Output, F2::Output>;
+        pub enum $Output<$($T),+> {
+            $($Variant($T),)+
+        }
 
-    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
-        let this = unsafe { self.get_unchecked_mut() };
+        impl<$($F: Future),+> Future for $Select<$($F),+> {
+            type Output = $Output<$($F::Output),+>;
 
-        // 先轮询第一个 future
-        if !this.future1_done {
-            match this.future1.as_mut().poll(cx) {
-                Poll::Ready(output) => {
-                    this.future1_done = true;
-                    return Poll::Ready(Select2Output::Future1(output));
-                }
-                Poll::Pending => {}
+            fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+                // 子 future 只在原地被轮询, 不会被移动
+                let this = unsafe { self.get_unchecked_mut() };
+                $(
+                    if let Poll::Ready(output) = unsafe { Pin::new_unchecked(&mut this.$f) }.poll(cx) {
+                        return Poll::Ready($Output::$Variant(output));
+                    }
+                )+
+                // 都未完成, 等待子 future 唤醒
+                Poll::Pending
             }
         }
 
-        // 再轮询第二个 future
-        if !this.future2_done {
-            match this.future2.as_mut().poll(cx) {
-                Poll::Ready(output) => {
-                    this.future2_done = true;
-                    return Poll::Ready(Select2Output::Future2(output));
-                }
-                Poll::Pending => {}
+        impl<$($F: Future),+> $Select<$($F),+> {
+            #[allow(unused)]
+            pub fn new($($f: $F),+) -> Self {
+                $Select { $($f,)+ }
             }
         }
 
-        // 如果都未完成,继续等待
-        cx.waker().wake_by_ref();
-        Poll::Pending
-    }
+        #[allow(unused)]
+        pub fn $select<$($F: Future),+>($($f: $F),+) -> $Select<$($F),+> {
+            $Select::new($($f),+)
+        }
+    };
+}
+
+impl_select!(Select2, select, Select2Output; F1 T1 future1 Future1, F2 T2 future2 Future2);
+impl_select!(Select3, select3, Select3Output; F1 T1 future1 Future1, F2 T2 future2 Future2, F3 T3 future3 Future3);
+impl_select!(Select4, select4, Select4Output; F1 T1 future1 Future1, F2 T2 future2 Future2, F3 T3 future3 Future3, F4 T4 future4 Future4);
+impl_select!(Select5, select5, Select5Output; F1 T1 future1 Future1, F2 T2 future2 Future2, F3 T3 future3 Future3, F4 T4 future4 Future4, F5 T5 future5 Future5);
+
+/// 等待数量不定的 future 中任意一个完成, 返回结果和该 future 的序号
+#[must_use = "futures do nothing unless you `.await` or poll them"]
+pub struct SelectAll<F: Future> {
+    futures: Pin<Box<[F]>>,
 }
 
-impl<F1, F2> Unpin for Select2<F1, F2>
-where
-    F1: Future,
-    F2: Future,
-{
+impl<F: Future> Future for SelectAll<F> {
+    type Output = (F::Output, usize);
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let futures = unsafe { self.futures.as_mut().get_unchecked_mut() };
+        for (index, future) in futures.iter_mut().enumerate() {
+            if let Poll::Ready(output) = unsafe { Pin::new_unchecked(future) }.poll(cx) {
+                return Poll::Ready((output, index));
+            }
+        }
+        Poll::Pending
+    }
 }
 
-#[allow(unused)]
-pub fn select<F1, F2>(future1: F1, future2: F2) -> Select2<F1, F2>
-where
-    F1: Future,
-    F2: Future,
-{
-    Select2::new(future1, future2)
+/// futures 不能为空, 否则永远不会完成
+pub fn select_all<F: Future>(futures: impl IntoIterator<Item = F>) -> SelectAll<F> {
+    let futures: Box<[F]> = futures.into_iter().collect();
+    SelectAll {
+        futures: Box::into_pin(futures),
+    }
 }
 
+/// 等待多个分支中第一个完成的分支并执行其代码块, 自动包含 .await, 最多支持 5 个分支
+///
+/// ```ignore
+/// select! {
+///     frame = uart.read_frame(&mut buf, 10) => { handle(frame) },
+///     _ = sys::sleep_ms(1000) => { timeout() },
+///     _ = token.cancelled() => { return -1 },
+/// }
+/// ```
 #[macro_export]
 macro_rules! select {
-    // 简化版本: 只支持两个分支
     {
-        $pat1:pat = $fut1:expr => $body1:block,
-        $pat2:pat = $fut2:expr => $body2:block $(,)?
+        $pat1:pat = $fut1:expr => $body1:expr,
+        $pat2:pat = $fut2:expr => $body2:expr $(,)?
     } => {{
         match $crate::sys::select($fut1, $fut2).await {
             $crate::sys::Select2Output::Future1($pat1) => $body1,
             $crate::sys::Select2Output::Future2($pat2) => $body2,
         }
     }};
-}
\ No newline at end of file
+    {
+        $pat1:pat = $fut1:expr => $body1:expr,
+        $pat2:pat = $fut2:expr => $body2:expr,
+        $pat3:pat = $fut3:expr => $body3:expr $(,)?
+    } => {{
+        match $crate::sys::select3($fut1, $fut2, $fut3).await {
+            $crate::sys::Select3Output::Future1($pat1) => $body1,
+            $crate::sys::Select3Output::Future2($pat2) => $body2,
+            $crate::sys::Select3Output::Future3($pat3) => $body3,
+        }
+    }};
+    {
+        $pat1:pat = $fut1:expr => $body1:expr,
+        $pat2:pat = $fut2:expr => $body2:expr,
+        $pat3:pat = $fut3:expr => $body3:expr,
+        $pat4:pat = $fut4:expr => $body4:expr $(,)?
+    } => {{
+        match $crate::sys::select4($fut1, $fut2, $fut3, $fut4).await {
+            $crate::sys::Select4Output::Future1($pat1) => $body1,
+            $crate::sys::Select4Output::Future2($pat2) => $body2,
+            $crate::sys::Select4Output::Future3($pat3) => $body3,
+            $crate::sys::Select4Output::Future4($pat4) => $body4,
+        }
+    }};
+    {
+        $pat1:pat = $fut1:expr => $body1:expr,
+        $pat2:pat = $fut2:expr => $body2:expr,
+        $pat3:pat = $fut3:expr => $body3:expr,
+        $pat4:pat = $fut4:expr => $body4:expr,
+        $pat5:pat = $fut5:expr => $body5:expr $(,)?
+    } => {{
+        match $crate::sys::select5($fut1, $fut2, $fut3, $fut4, $fut5).await {
+            $crate::sys::Select5Output::Future1($pat1) => $body1,
+            $crate::sys::Select5Output::Future2($pat2) => $body2,
+            $crate::sys::Select5Output::Future3($pat3) => $body3,
+            $crate::sys::Select5Output::Future4($pat4) => $body4,
+            $crate::sys::Select5Output::Future5($pat5) => $body5,
+        }
+    }};
+}
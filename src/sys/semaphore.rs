@@ -0,0 +1,107 @@
+use core::cell::{Cell, RefCell};
+use core::future::Future;
+use core::pin::Pin;
+use core::task::{Context, Poll};
+
+use crate::sys::wait_list::WaitList;
+
+/// 计数信号量, 没有可用许可时挂起任务, 许可释放时按先后顺序唤醒
+pub struct Semaphore {
+    permits: Cell<usize>,
+    waiters: RefCell<WaitList>,
+}
+
+impl Semaphore {
+    pub const fn new(permits: usize) -> Self {
+        Semaphore {
+            permits: Cell::new(permits),
+            waiters: RefCell::new(WaitList::new()),
+        }
+    }
+
+    /// 获取一个许可, 返回的许可在离开作用域时自动归还
+    pub fn acquire(&self) -> SemaphoreAcquireFuture<'_> {
+        SemaphoreAcquireFuture {
+            semaphore: self,
+            key: None,
+        }
+    }
+
+    /// 尝试获取一个许可, 没有可用许可时立即返回 None
+    pub fn try_acquire(&self) -> Option<SemaphorePermit<'_>> {
+        if self.permits.get() == 0 {
+            None
+        } else {
+            self.permits.set(self.permits.get() - 1);
+            Some(SemaphorePermit { semaphore: self })
+        }
+    }
+
+    /// 当前可用的许可数量
+    pub fn available_permits(&self) -> usize {
+        self.permits.get()
+    }
+
+    /// 增加许可并唤醒对应数量的等待者
+    pub fn add_permits(&self, n: usize) {
+        self.permits.set(self.permits.get() + n);
+        let mut waiters = self.waiters.borrow_mut();
+        for _ in 0..n {
+            if !waiters.notify_one() {
+                break;
+            }
+        }
+    }
+}
+
+#[must_use = "futures do nothing unless you `.await` or poll them"]
+pub struct SemaphoreAcquireFuture<'a> {
+    semaphore: &'a Semaphore,
+    key: Option<usize>,
+}
+
+impl<'a> Future for SemaphoreAcquireFuture<'a> {
+    type Output = SemaphorePermit<'a>;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let this = self.get_mut();
+        let semaphore = this.semaphore;
+        let mut waiters = semaphore.waiters.borrow_mut();
+        if semaphore.permits.get() > 0 {
+            semaphore.permits.set(semaphore.permits.get() - 1);
+            waiters.remove(&mut this.key);
+            Poll::Ready(SemaphorePermit { semaphore })
+        } else {
+            waiters.register(&mut this.key, cx.waker());
+            Poll::Pending
+        }
+    }
+}
+
+impl Drop for SemaphoreAcquireFuture<'_> {
+    fn drop(&mut self) {
+        let mut waiters = self.semaphore.waiters.borrow_mut();
+        // 被唤醒后取消等待, 将唤醒转交给下一个等待者
+        if waiters.remove(&mut self.key) && self.semaphore.permits.get() > 0 {
+            waiters.notify_one();
+        }
+    }
+}
+
+/// 信号量许可, 离开作用域时归还
+pub struct SemaphorePermit<'a> {
+    semaphore: &'a Semaphore,
+}
+
+impl SemaphorePermit<'_> {
+    /// 不归还许可, 相当于永久减少信号量的许可数量
+    pub fn forget(self) {
+        core::mem::forget(self);
+    }
+}
+
+impl Drop for SemaphorePermit<'_> {
+    fn drop(&mut self) {
+        self.semaphore.add_permits(1);
+    }
+}
@@ -2,21 +2,31 @@ use core::future::Future;
 use core::pin::Pin;
 use core::task::{Context, Poll};
 
-use crate::sys::SimpleOs;
+use crate::executor::TimerHandle;
+use crate::sys::{Duration, Instant, SimpleOs};
 
 pub struct SleepMsFuture {
-    escape: u32,
+    deadline: Instant,
+    timer: TimerHandle, // 提前丢弃时注销定时器
+}
+
+impl SleepMsFuture {
+    /// 到期时间
+    pub fn deadline(&self) -> Instant {
+        self.deadline
+    }
 }
 
 impl Future for SleepMsFuture {
     type Output = ();
 
     fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
-        let current_tick = SimpleOs::device().get_systick().get_system_ms();
-        if current_tick >= self.escape {
+        let this = self.get_mut();
+        if Instant::now() >= this.deadline {
             Poll::Ready(())
         } else {
-            cx.waker().wake_by_ref();
+            // 注册到执行器的定时器服务, 到期后唤醒
+            this.timer.register(this.deadline, cx.waker());
             Poll::Pending
         }
     }
@@ -24,10 +34,19 @@ impl Future for SleepMsFuture {
 
 #[allow(unused)]
 pub fn sleep_ms(ms: u32) -> SleepMsFuture {
-    unsafe {
-        SleepMsFuture {
-            escape: ms + SimpleOs::device().get_systick().get_system_ms(),
-        }
+    sleep(Duration::from_millis(ms as u64))
+}
+
+#[allow(unused)]
+pub fn sleep(duration: Duration) -> SleepMsFuture {
+    sleep_until(Instant::now() + duration)
+}
+
+#[allow(unused)]
+pub fn sleep_until(deadline: Instant) -> SleepMsFuture {
+    SleepMsFuture {
+        deadline,
+        timer: TimerHandle::new(),
     }
 }
 
@@ -40,6 +59,6 @@ pub fn get_system_ms() -> u32 {
 #[allow(unused)]
 #[inline]
 pub fn delay_ms(ms: u32) {
-    let start = get_system_ms();
-    while get_system_ms().wrapping_sub(start) < ms {}
+    let start = Instant::now();
+    while start.elapsed() < Duration::from_millis(ms as u64) {}
 }
@@ -0,0 +1,48 @@
+use alloc::boxed::Box;
+
+use crate::executor::Executor;
+use crate::sys::{Duration, Instant};
+
+/// 软件定时器句柄, 丢弃句柄不会停止定时器
+///
+/// 回调在执行器上下文中调用 (不属于任何任务), 应尽快返回, 耗时操作可在回调中创建任务;
+/// 执行器没有任务时退出, 不再触发定时器
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct SoftTimer {
+    id: u32,
+}
+
+impl SoftTimer {
+    /// ms 毫秒后调用一次回调
+    pub fn once(ms: u32, callback: impl FnOnce() + 'static) -> Self {
+        let mut callback = Some(callback);
+        let id = Executor::add_soft_timer(
+            Instant::now() + Duration::from_millis(ms as u64),
+            None,
+            Box::new(move || {
+                if let Some(callback) = callback.take() {
+                    callback();
+                }
+            }),
+        );
+        SoftTimer { id }
+    }
+
+    /// 每隔 period_ms 毫秒调用一次回调, 按固定节拍触发, 错过的节拍被跳过
+    pub fn periodic(period_ms: u32, callback: impl FnMut() + 'static) -> Self {
+        assert!(period_ms > 0, "timer period must be non-zero");
+        let period = Duration::from_millis(period_ms as u64);
+        let id = Executor::add_soft_timer(Instant::now() + period, Some(period), Box::new(callback));
+        SoftTimer { id }
+    }
+
+    /// 停止定时器, 返回定时器是否仍在运行
+    pub fn cancel(&self) -> bool {
+        Executor::cancel_soft_timer(self.id)
+    }
+
+    /// 定时器是否仍在运行, 单次定时器触发后不再运行
+    pub fn is_active(&self) -> bool {
+        Executor::soft_timer_active(self.id)
+    }
+}
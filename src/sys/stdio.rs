@@ -0,0 +1,253 @@
+use alloc::collections::VecDeque;
+use alloc::rc::Rc;
+use alloc::vec::Vec;
+use core::cell::RefCell;
+use core::future::poll_fn;
+use core::task::{Context, Poll, Waker};
+
+use crate::executor::Executor;
+use crate::sys::{self, SimpleOs};
+
+const TTY_POLL_INTERVAL_MS: u32 = 10; // 从终端读取时无输入的轮询间隔
+const PIPE_CAPACITY: usize = 512; // 管道缓冲区容量
+
+/// 输出流, 任务的标准输出可重定向到实现此接口的对象
+pub trait OutputStream {
+    /// 写入数据, 写入失败时丢弃
+    fn write(&mut self, data: &[u8]);
+    fn flush(&mut self) {}
+
+    /// 写入数据, 返回已写入的字节数; 暂时无法写入时登记唤醒器并返回 Pending
+    ///
+    /// 默认实现直接调用 write, 有容量限制的输出流 (如管道) 应重写此方法
+    fn poll_write(&mut self, _cx: &mut Context<'_>, data: &[u8]) -> Poll<usize> {
+        self.write(data);
+        Poll::Ready(data.len())
+    }
+}
+
+/// 输入流, 任务的标准输入可重定向到实现此接口的对象
+pub trait InputStream {
+    /// 读取数据, 返回 0 表示输入已结束; 暂无数据时登记唤醒器并返回 Pending
+    fn poll_read(&mut self, cx: &mut Context<'_>, buf: &mut [u8]) -> Poll<usize>;
+}
+
+/// 任务的标准输出, 由任务及其子任务共享, 最后一个持有者结束时释放
+pub type Stdout = Rc<RefCell<dyn OutputStream>>;
+
+/// 任务的标准输入, 由任务及其子任务共享, 最后一个持有者结束时释放
+pub type Stdin = Rc<RefCell<dyn InputStream>>;
+
+/// 当前任务的标准输出是否被重定向
+pub fn is_stdout_redirected() -> bool {
+    Executor::stdout().is_some()
+}
+
+/// 当前任务的标准输入是否被重定向
+pub fn is_stdin_redirected() -> bool {
+    Executor::stdin().is_some()
+}
+
+/// 写入当前任务的标准输出, 未重定向时写入终端
+pub fn stdout_write(data: &[u8]) {
+    if let Some(stdout) = Executor::stdout() {
+        // 输出流内部再次打印时直接写入终端, 避免重复借用
+        if let Ok(mut stdout) = stdout.try_borrow_mut() {
+            stdout.write(data);
+            return;
+        }
+    }
+    if SimpleOs::is_initialized() {
+        SimpleOs::tty().tty_write(data);
+    }
+}
+
+/// 写入当前任务的标准输出, 输出流已满时挂起直到全部写入, 未重定向时写入终端
+///
+/// 输出大量数据 (如 cat 文件) 时应使用此函数, 以便管道满时让出执行权
+pub async fn stdout_write_all(data: &[u8]) {
+    let stdout = match Executor::stdout() {
+        Some(stdout) => stdout,
+        None => return stdout_write(data),
+    };
+    let mut written = 0;
+    while written < data.len() {
+        written += poll_fn(|cx| match stdout.try_borrow_mut() {
+            Ok(mut stdout) => stdout.poll_write(cx, &data[written..]),
+            Err(_) => {
+                // 其他任务正在写入, 稍后重试
+                cx.waker().wake_by_ref();
+                Poll::Pending
+            }
+        })
+        .await;
+    }
+}
+
+/// 刷新当前任务的标准输出
+pub fn stdout_flush() {
+    if let Some(stdout) = Executor::stdout() {
+        if let Ok(mut stdout) = stdout.try_borrow_mut() {
+            stdout.flush();
+            return;
+        }
+    }
+    if SimpleOs::is_initialized() {
+        SimpleOs::tty().tty_flush();
+    }
+}
+
+/// `print!` 输出后刷新, 仅在输出到终端时刷新
+///
+/// 重定向到文件时不逐行刷新, 以免每行都同步文件系统; 文件在任务结束关闭输出时刷新
+#[doc(hidden)]
+pub fn print_flush() {
+    if Executor::stdout().is_none() && SimpleOs::is_initialized() {
+        SimpleOs::tty().tty_flush();
+    }
+}
+
+/// 从当前任务的标准输入读取数据, 返回 0 表示输入已结束
+///
+/// 未重定向时从终端读取, 至少读到 1 个字节才返回
+pub async fn stdin_read(buf: &mut [u8]) -> usize {
+    if buf.is_empty() {
+        return 0;
+    }
+    match Executor::stdin() {
+        Some(stdin) => {
+            poll_fn(|cx| match stdin.try_borrow_mut() {
+                Ok(mut stdin) => stdin.poll_read(cx, buf),
+                Err(_) => {
+                    // 其他任务正在读取, 稍后重试
+                    cx.waker().wake_by_ref();
+                    Poll::Pending
+                }
+            })
+            .await
+        }
+        None => loop {
+            let count = SimpleOs::tty().tty_read(buf);
+            if count > 0 {
+                return count;
+            }
+            sys::sleep_ms(TTY_POLL_INTERVAL_MS).await;
+        },
+    }
+}
+
+struct PipeBuffer {
+    data: VecDeque<u8>,
+    writer_closed: bool,
+    reader_closed: bool,
+    reader_waker: Option<Waker>,
+    writer_wakers: Vec<Waker>, // 写端由任务及其子任务共享, 可能有多个任务等待
+}
+
+impl PipeBuffer {
+    fn wake_reader(&mut self) {
+        if let Some(waker) = self.reader_waker.take() {
+            waker.wake();
+        }
+    }
+
+    fn wake_writers(&mut self) {
+        for waker in self.writer_wakers.drain(..) {
+            waker.wake();
+        }
+    }
+}
+
+/// 管道写端, 丢弃后读端读完剩余数据即结束
+pub struct PipeWriter {
+    buffer: Rc<RefCell<PipeBuffer>>,
+}
+
+/// 管道读端, 丢弃后写入的数据被丢弃
+pub struct PipeReader {
+    buffer: Rc<RefCell<PipeBuffer>>,
+}
+
+/// 创建内存管道, 用于连接两个任务的标准输出和标准输入
+///
+/// 缓冲区容量为 `PIPE_CAPACITY`, 满时 `poll_write` (`stdout_write_all`) 挂起写端直到读端取走数据;
+/// 同步的 `write` (`print!`) 无法挂起, 会暂时超出容量
+pub fn pipe() -> (PipeWriter, PipeReader) {
+    let buffer = Rc::new(RefCell::new(PipeBuffer {
+        data: VecDeque::with_capacity(PIPE_CAPACITY),
+        writer_closed: false,
+        reader_closed: false,
+        reader_waker: None,
+        writer_wakers: Vec::new(),
+    }));
+    (
+        PipeWriter {
+            buffer: buffer.clone(),
+        },
+        PipeReader { buffer },
+    )
+}
+
+impl OutputStream for PipeWriter {
+    fn write(&mut self, data: &[u8]) {
+        let mut buffer = self.buffer.borrow_mut();
+        if buffer.reader_closed {
+            return;
+        }
+        buffer.data.extend(data.iter());
+        buffer.wake_reader();
+    }
+
+    fn poll_write(&mut self, cx: &mut Context<'_>, data: &[u8]) -> Poll<usize> {
+        let mut buffer = self.buffer.borrow_mut();
+        if buffer.reader_closed {
+            return Poll::Ready(data.len());
+        }
+        let count = PIPE_CAPACITY.saturating_sub(buffer.data.len()).min(data.len());
+        if count == 0 && !data.is_empty() {
+            if !buffer.writer_wakers.iter().any(|w| w.will_wake(cx.waker())) {
+                buffer.writer_wakers.push(cx.waker().clone());
+            }
+            return Poll::Pending;
+        }
+        buffer.data.extend(data[..count].iter());
+        buffer.wake_reader();
+        Poll::Ready(count)
+    }
+}
+
+impl Drop for PipeWriter {
+    fn drop(&mut self) {
+        let mut buffer = self.buffer.borrow_mut();
+        buffer.writer_closed = true;
+        buffer.wake_reader();
+    }
+}
+
+impl InputStream for PipeReader {
+    fn poll_read(&mut self, cx: &mut Context<'_>, buf: &mut [u8]) -> Poll<usize> {
+        let mut buffer = self.buffer.borrow_mut();
+        if buffer.data.is_empty() {
+            if buffer.writer_closed {
+                return Poll::Ready(0);
+            }
+            buffer.reader_waker = Some(cx.waker().clone());
+            return Poll::Pending;
+        }
+        let count = buf.len().min(buffer.data.len());
+        for (dst, src) in buf.iter_mut().zip(buffer.data.drain(..count)) {
+            *dst = src;
+        }
+        buffer.wake_writers();
+        Poll::Ready(count)
+    }
+}
+
+impl Drop for PipeReader {
+    fn drop(&mut self) {
+        let mut buffer = self.buffer.borrow_mut();
+        buffer.reader_closed = true;
+        buffer.data.clear();
+        buffer.wake_writers();
+    }
+}
@@ -0,0 +1,211 @@
+use core::cmp::Ordering;
+use core::ops::{Add, AddAssign, Sub, SubAssign};
+
+pub use core::time::Duration;
+
+use crate::singleton;
+use crate::sys::get_system_ms;
+
+/// 两个时间点之间可比较的最大跨度 (约24.8天)
+///
+/// 毫秒计数器为 u32, 约49.7天回绕一次, 比较时按回绕差值的符号判断先后,
+/// 因此只有相距小于该跨度的时间点才能正确比较
+pub const MAX_DURATION_MS: u32 = i32::MAX as u32;
+
+/// 基于系统毫秒计数器的时间点, 比较和运算均支持计数器回绕
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct Instant(u32);
+
+impl Instant {
+    /// 当前时间点
+    pub fn now() -> Self {
+        Instant(get_system_ms())
+    }
+
+    pub const fn from_millis(ms: u32) -> Self {
+        Instant(ms)
+    }
+
+    /// 计数器原始值
+    pub const fn as_millis(&self) -> u32 {
+        self.0
+    }
+
+    /// 从该时间点到现在经过的时长
+    pub fn elapsed(&self) -> Duration {
+        Instant::now().duration_since(*self)
+    }
+
+    /// 与更早时间点的间隔, earlier 晚于 self 时返回 None
+    pub fn checked_duration_since(&self, earlier: Instant) -> Option<Duration> {
+        let diff = self.0.wrapping_sub(earlier.0) as i32;
+        if diff >= 0 {
+            Some(Duration::from_millis(diff as u64))
+        } else {
+            None
+        }
+    }
+
+    /// 与更早时间点的间隔, earlier 晚于 self 时返回 0
+    pub fn duration_since(&self, earlier: Instant) -> Duration {
+        self.checked_duration_since(earlier).unwrap_or(Duration::ZERO)
+    }
+
+    /// 时长超过 MAX_DURATION_MS 时返回 None
+    pub fn checked_add(&self, duration: Duration) -> Option<Instant> {
+        let ms = duration.as_millis();
+        if ms > MAX_DURATION_MS as u128 {
+            None
+        } else {
+            Some(Instant(self.0.wrapping_add(ms as u32)))
+        }
+    }
+
+    /// 时长超过 MAX_DURATION_MS 时返回 None
+    pub fn checked_sub(&self, duration: Duration) -> Option<Instant> {
+        let ms = duration.as_millis();
+        if ms > MAX_DURATION_MS as u128 {
+            None
+        } else {
+            Some(Instant(self.0.wrapping_sub(ms as u32)))
+        }
+    }
+}
+
+impl Ord for Instant {
+    fn cmp(&self, other: &Self) -> Ordering {
+        (self.0.wrapping_sub(other.0) as i32).cmp(&0)
+    }
+}
+
+impl PartialOrd for Instant {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Add<Duration> for Instant {
+    type Output = Instant;
+
+    /// 时长超过 MAX_DURATION_MS 时截断为 MAX_DURATION_MS
+    fn add(self, rhs: Duration) -> Instant {
+        self.checked_add(rhs)
+            .unwrap_or(Instant(self.0.wrapping_add(MAX_DURATION_MS)))
+    }
+}
+
+impl AddAssign<Duration> for Instant {
+    fn add_assign(&mut self, rhs: Duration) {
+        *self = *self + rhs;
+    }
+}
+
+impl Sub<Duration> for Instant {
+    type Output = Instant;
+
+    /// 时长超过 MAX_DURATION_MS 时截断为 MAX_DURATION_MS
+    fn sub(self, rhs: Duration) -> Instant {
+        self.checked_sub(rhs)
+            .unwrap_or(Instant(self.0.wrapping_sub(MAX_DURATION_MS)))
+    }
+}
+
+impl SubAssign<Duration> for Instant {
+    fn sub_assign(&mut self, rhs: Duration) {
+        *self = *self - rhs;
+    }
+}
+
+impl Sub<Instant> for Instant {
+    type Output = Duration;
+
+    fn sub(self, rhs: Instant) -> Duration {
+        self.duration_since(rhs)
+    }
+}
+
+/// 64位扩展计数器, 记录 u32 毫秒计数器的回绕次数
+struct TickExtender {
+    last: u32,
+    wraps: u32,
+}
+
+singleton!(TickExtender { last: 0, wraps: 0 });
+
+impl TickExtender {
+    fn extend(&mut self, now: u32) -> u64 {
+        if now < self.last {
+            self.wraps = self.wraps.wrapping_add(1);
+        }
+        self.last = now;
+        ((self.wraps as u64) << 32) | now as u64
+    }
+}
+
+/// 获取64位系统毫秒数, 不会回绕
+///
+/// 通过检测 u32 计数器回绕实现, 两次调用间隔必须小于约49.7天,
+/// 执行器在每次调度循环中都会调用, 应用无需额外处理
+pub fn get_system_ms64() -> u64 {
+    TickExtender::get_mut().extend(get_system_ms())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const NEAR_WRAP: u32 = u32::MAX - 10;
+
+    #[test]
+    fn ordering_across_wrap() {
+        let before = Instant::from_millis(NEAR_WRAP);
+        let after = before + Duration::from_millis(20);
+        assert_eq!(after.as_millis(), 9);
+        assert!(before < after);
+        assert!(after > before);
+        assert_eq!(after.max(before), after);
+        assert_eq!(after - before, Duration::from_millis(20));
+        assert_eq!(after.checked_duration_since(before), Some(Duration::from_millis(20)));
+        assert_eq!(before.checked_duration_since(after), None);
+        assert_eq!(before.duration_since(after), Duration::ZERO);
+        assert_eq!(after - Duration::from_millis(20), before);
+    }
+
+    #[test]
+    fn ordering_limit() {
+        let start = Instant::from_millis(NEAR_WRAP);
+        let limit = Instant::from_millis(NEAR_WRAP.wrapping_add(MAX_DURATION_MS));
+        assert!(start < limit);
+        assert_eq!(limit - start, Duration::from_millis(MAX_DURATION_MS as u64));
+        // 超过可比较的最大跨度后先后关系反转
+        let beyond = Instant::from_millis(NEAR_WRAP.wrapping_add(MAX_DURATION_MS).wrapping_add(1));
+        assert!(beyond < start);
+    }
+
+    #[test]
+    fn add_sub_saturate_at_max_duration() {
+        let start = Instant::from_millis(NEAR_WRAP);
+        let long = Duration::from_secs(60 * 60 * 24 * 30);
+        assert_eq!(start.checked_add(long), None);
+        assert_eq!(start.checked_sub(long), None);
+        assert_eq!(start + long, start + Duration::from_millis(MAX_DURATION_MS as u64));
+        assert_eq!(start - long, start - Duration::from_millis(MAX_DURATION_MS as u64));
+        assert!(start + long > start);
+        assert!(start - long < start);
+        let mut t = start;
+        t += Duration::from_millis(15);
+        assert_eq!(t.as_millis(), 4);
+        t -= Duration::from_millis(5);
+        assert_eq!(t.as_millis(), u32::MAX);
+    }
+
+    #[test]
+    fn tick_extender() {
+        let mut ext = TickExtender { last: 0, wraps: 0 };
+        assert_eq!(ext.extend(100), 100);
+        assert_eq!(ext.extend(u32::MAX), u32::MAX as u64);
+        assert_eq!(ext.extend(5), (1 << 32) | 5);
+        assert_eq!(ext.extend(5), (1 << 32) | 5);
+        assert_eq!(ext.extend(1), (2 << 32) | 1);
+    }
+}
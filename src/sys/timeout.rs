@@ -0,0 +1,79 @@
+use core::future::Future;
+use core::pin::Pin;
+use core::task::{Context, Poll};
+
+use crate::sys::{sleep_ms, sleep_until, Instant, SleepMsFuture};
+
+/// 等待超时
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Elapsed;
+
+/// 为 future 增加超时, 超时后返回 Elapsed 并释放 future
+#[must_use = "futures do nothing unless you `.await` or poll them"]
+pub struct Timeout<F> {
+    future: Option<F>, // 超时后原地释放
+    sleep: SleepMsFuture,
+}
+
+impl<F> Timeout<F> {
+    /// 超时时间点
+    pub fn deadline(&self) -> Instant {
+        self.sleep.deadline()
+    }
+}
+
+impl<F: Future> Future for Timeout<F> {
+    type Output = Result<F::Output, Elapsed>;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        // future 只在原地被轮询, 不会被移动
+        let this = unsafe { self.get_unchecked_mut() };
+        let future = match this.future.as_mut() {
+            Some(future) => future,
+            None => return Poll::Ready(Err(Elapsed)),
+        };
+        // 先轮询 future, 已完成的结果优先于超时
+        if let Poll::Ready(output) = unsafe { Pin::new_unchecked(future) }.poll(cx) {
+            return Poll::Ready(Ok(output));
+        }
+        match Pin::new(&mut this.sleep).poll(cx) {
+            Poll::Ready(()) => {
+                // 在原地析构, 不违反 Pin 的约定
+                this.future = None;
+                Poll::Ready(Err(Elapsed))
+            }
+            Poll::Pending => Poll::Pending,
+        }
+    }
+}
+
+/// 在 ms 毫秒内等待 future 完成
+#[allow(unused)]
+pub fn timeout<F: Future>(ms: u32, future: F) -> Timeout<F> {
+    Timeout {
+        future: Some(future),
+        sleep: sleep_ms(ms),
+    }
+}
+
+/// 在 deadline 之前等待 future 完成
+#[allow(unused)]
+pub fn timeout_at<F: Future>(deadline: Instant, future: F) -> Timeout<F> {
+    Timeout {
+        future: Some(future),
+        sleep: sleep_until(deadline),
+    }
+}
+
+/// 为 future 提供 `.with_timeout(ms)` 和 `.with_deadline(deadline)`
+pub trait TimeoutExt: Future + Sized {
+    fn with_timeout(self, ms: u32) -> Timeout<Self> {
+        timeout(ms, self)
+    }
+
+    fn with_deadline(self, deadline: Instant) -> Timeout<Self> {
+        timeout_at(deadline, self)
+    }
+}
+
+impl<F: Future> TimeoutExt for F {}
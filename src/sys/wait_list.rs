@@ -0,0 +1,103 @@
+use alloc::collections::VecDeque;
+use core::task::Waker;
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum WaitState {
+    Waiting,        // 等待中
+    Notified(bool), // 已被唤醒, true 表示唤醒权需要在取消时转交给下一个等待者
+}
+
+struct WaitEntry {
+    key: usize,
+    waker: Waker,
+    state: WaitState,
+}
+
+/// 等待者队列, 供同步原语挂起和唤醒任务
+///
+/// 每个等待中的 future 持有一个 key, 被唤醒后仍保留在队列中直到 future
+/// 自己取走通知或被丢弃, 以保证 future 被取消时唤醒不会丢失
+pub(crate) struct WaitList {
+    next_key: usize,
+    entries: VecDeque<WaitEntry>,
+}
+
+impl WaitList {
+    pub const fn new() -> Self {
+        WaitList {
+            next_key: 0,
+            entries: VecDeque::new(),
+        }
+    }
+
+    /// 登记或更新等待者, 已被唤醒的等待者会恢复为等待状态并保持原有顺序
+    pub fn register(&mut self, key: &mut Option<usize>, waker: &Waker) {
+        if let Some(k) = *key {
+            if let Some(entry) = self.entries.iter_mut().find(|e| e.key == k) {
+                if !entry.waker.will_wake(waker) {
+                    entry.waker = waker.clone();
+                }
+                entry.state = WaitState::Waiting;
+                return;
+            }
+        }
+        let k = self.next_key;
+        self.next_key = self.next_key.wrapping_add(1);
+        self.entries.push_back(WaitEntry {
+            key: k,
+            waker: waker.clone(),
+            state: WaitState::Waiting,
+        });
+        *key = Some(k);
+    }
+
+    /// 如果等待者已被唤醒, 将其移出队列并返回 true
+    pub fn take_notified(&mut self, key: &mut Option<usize>) -> bool {
+        if let Some(k) = *key {
+            if let Some(index) = self.entries.iter().position(|e| e.key == k) {
+                if self.entries[index].state != WaitState::Waiting {
+                    self.entries.remove(index);
+                    *key = None;
+                    return true;
+                }
+            }
+        }
+        false
+    }
+
+    /// 移除等待者, 返回它是否持有未使用的唤醒权
+    pub fn remove(&mut self, key: &mut Option<usize>) -> bool {
+        if let Some(k) = key.take() {
+            if let Some(index) = self.entries.iter().position(|e| e.key == k) {
+                let entry = self.entries.remove(index);
+                return entry.map(|e| e.state) == Some(WaitState::Notified(true));
+            }
+        }
+        false
+    }
+
+    /// 唤醒最早的一个等待者, 没有等待者时返回 false
+    pub fn notify_one(&mut self) -> bool {
+        if let Some(entry) = self
+            .entries
+            .iter_mut()
+            .find(|e| e.state == WaitState::Waiting)
+        {
+            entry.state = WaitState::Notified(true);
+            entry.waker.wake_by_ref();
+            true
+        } else {
+            false
+        }
+    }
+
+    /// 唤醒所有等待者
+    pub fn notify_all(&mut self) {
+        for entry in self.entries.iter_mut() {
+            if entry.state == WaitState::Waiting {
+                entry.state = WaitState::Notified(false);
+                entry.waker.wake_by_ref();
+            }
+        }
+    }
+}
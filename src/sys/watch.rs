@@ -0,0 +1,174 @@
+use alloc::rc::Rc;
+use core::cell::{Cell, Ref, RefCell};
+use core::future::Future;
+use core::pin::Pin;
+use core::task::{Context, Poll};
+
+use crate::sys::wait_list::WaitList;
+
+/// 发送者已关闭
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct RecvError;
+
+/// 所有接收者已关闭, 携带未发送的数据
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct SendError<T>(pub T);
+
+struct Shared<T> {
+    value: RefCell<T>,
+    version: Cell<u32>,
+    sender_closed: Cell<bool>,
+    receivers: Cell<usize>,
+    waiters: RefCell<WaitList>,
+}
+
+/// 创建状态观察通道, 只保存最新的值
+///
+/// 接收者通过 changed() 等待值更新, 通过 borrow() 读取当前值;
+/// 发送者被丢弃 (例如所在任务退出) 后 changed() 返回 RecvError
+pub fn channel<T>(init: T) -> (Sender<T>, Receiver<T>) {
+    let shared = Rc::new(Shared {
+        value: RefCell::new(init),
+        version: Cell::new(0),
+        sender_closed: Cell::new(false),
+        receivers: Cell::new(1),
+        waiters: RefCell::new(WaitList::new()),
+    });
+    (
+        Sender {
+            shared: shared.clone(),
+        },
+        Receiver {
+            shared,
+            seen_version: 0,
+        },
+    )
+}
+
+pub struct Sender<T> {
+    shared: Rc<Shared<T>>,
+}
+
+impl<T> Sender<T> {
+    /// 更新值并唤醒所有接收者, 没有接收者时返回错误
+    pub fn send(&self, value: T) -> Result<(), SendError<T>> {
+        if self.shared.receivers.get() == 0 {
+            return Err(SendError(value));
+        }
+        self.send_replace(value);
+        Ok(())
+    }
+
+    /// 无论是否有接收者都更新值, 返回旧值
+    pub fn send_replace(&self, value: T) -> T {
+        let old = self.shared.value.replace(value);
+        self.shared.version.set(self.shared.version.get().wrapping_add(1));
+        self.shared.waiters.borrow_mut().notify_all();
+        old
+    }
+
+    /// 读取当前值
+    pub fn borrow(&self) -> Ref<'_, T> {
+        self.shared.value.borrow()
+    }
+
+    /// 创建新的接收者, 当前值视为已读
+    pub fn subscribe(&self) -> Receiver<T> {
+        self.shared.receivers.set(self.shared.receivers.get() + 1);
+        Receiver {
+            shared: self.shared.clone(),
+            seen_version: self.shared.version.get(),
+        }
+    }
+
+    pub fn receiver_count(&self) -> usize {
+        self.shared.receivers.get()
+    }
+}
+
+impl<T> Drop for Sender<T> {
+    fn drop(&mut self) {
+        self.shared.sender_closed.set(true);
+        self.shared.waiters.borrow_mut().notify_all();
+    }
+}
+
+pub struct Receiver<T> {
+    shared: Rc<Shared<T>>,
+    seen_version: u32,
+}
+
+impl<T> Receiver<T> {
+    /// 读取当前值, 不标记为已读
+    pub fn borrow(&self) -> Ref<'_, T> {
+        self.shared.value.borrow()
+    }
+
+    /// 读取当前值并标记为已读
+    pub fn borrow_and_update(&mut self) -> Ref<'_, T> {
+        self.seen_version = self.shared.version.get();
+        self.shared.value.borrow()
+    }
+
+    /// 是否有未读的更新
+    pub fn has_changed(&self) -> bool {
+        self.seen_version != self.shared.version.get()
+    }
+
+    /// 等待值更新, 发送者关闭后返回错误
+    pub fn changed(&mut self) -> ChangedFuture<'_, T> {
+        ChangedFuture {
+            receiver: self,
+            key: None,
+        }
+    }
+}
+
+impl<T> Clone for Receiver<T> {
+    fn clone(&self) -> Self {
+        self.shared.receivers.set(self.shared.receivers.get() + 1);
+        Receiver {
+            shared: self.shared.clone(),
+            seen_version: self.seen_version,
+        }
+    }
+}
+
+impl<T> Drop for Receiver<T> {
+    fn drop(&mut self) {
+        self.shared.receivers.set(self.shared.receivers.get() - 1);
+    }
+}
+
+#[must_use = "futures do nothing unless you `.await` or poll them"]
+pub struct ChangedFuture<'a, T> {
+    receiver: &'a mut Receiver<T>,
+    key: Option<usize>,
+}
+
+impl<T> Future for ChangedFuture<'_, T> {
+    type Output = Result<(), RecvError>;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let this = self.get_mut();
+        let shared = &this.receiver.shared;
+        let mut waiters = shared.waiters.borrow_mut();
+        if this.receiver.seen_version != shared.version.get() {
+            this.receiver.seen_version = shared.version.get();
+            waiters.remove(&mut this.key);
+            Poll::Ready(Ok(()))
+        } else if shared.sender_closed.get() {
+            waiters.remove(&mut this.key);
+            Poll::Ready(Err(RecvError))
+        } else {
+            waiters.register(&mut this.key, cx.waker());
+            Poll::Pending
+        }
+    }
+}
+
+impl<T> Drop for ChangedFuture<'_, T> {
+    fn drop(&mut self) {
+        self.receiver.shared.waiters.borrow_mut().remove(&mut self.key);
+    }
+}
@@ -1,12 +1,16 @@
 mod convert;
 mod crc16;
 mod ringbuf;
+mod spsc_ringbuf;
 mod singleton;
 mod lazy;
 
 #[allow(unused)]
 pub use ringbuf::RingBuf;
 
+#[allow(unused)]
+pub use spsc_ringbuf::SpscRingBuf;
+
 #[allow(unused)]
 pub use crc16::crc16;
 
@@ -0,0 +1,86 @@
+use core::cell::UnsafeCell;
+use core::mem::MaybeUninit;
+use core::sync::atomic::{AtomicUsize, Ordering};
+
+/// 单生产者单消费者无锁环形缓冲区, 可在中断和任务之间传递数据
+///
+/// 生产者 (例如中断) 只调用 push(), 消费者 (例如任务) 只调用 pop()/clear(),
+/// 双方无需关中断. 只使用原子读写, 不依赖 CAS 指令
+pub struct SpscRingBuf<T, const N: usize> {
+    buf: UnsafeCell<[MaybeUninit<T>; N]>,
+    head: AtomicUsize, // 写指针, 只由生产者修改
+    tail: AtomicUsize, // 读指针, 只由消费者修改
+}
+
+unsafe impl<T: Send, const N: usize> Sync for SpscRingBuf<T, N> {}
+
+impl<T: Copy, const N: usize> SpscRingBuf<T, N> {
+    /// 创建新缓冲区（N必须大于1）。
+    pub const fn new() -> Self {
+        assert!(N > 1, "RINGBUF SIZE ERR");
+        Self {
+            buf: UnsafeCell::new([const { MaybeUninit::uninit() }; N]),
+            head: AtomicUsize::new(0),
+            tail: AtomicUsize::new(0),
+        }
+    }
+
+    /// 返回缓冲区容量（实际可用大小）。
+    pub const fn capacity(&self) -> usize {
+        N - 1
+    }
+
+    /// 返回当前元素数量。
+    pub fn len(&self) -> usize {
+        let h = self.head.load(Ordering::Acquire);
+        let t = self.tail.load(Ordering::Acquire);
+        if h >= t { h - t } else { N - t + h }
+    }
+
+    /// 检查是否为空。
+    pub fn is_empty(&self) -> bool {
+        self.head.load(Ordering::Acquire) == self.tail.load(Ordering::Acquire)
+    }
+
+    /// 检查是否满。
+    pub fn is_full(&self) -> bool {
+        (self.head.load(Ordering::Acquire) + 1) % N == self.tail.load(Ordering::Acquire)
+    }
+
+    /// 添加一个元素，返回是否成功（失败表示满）。只能由生产者调用
+    pub fn push(&self, value: T) -> bool {
+        let head = self.head.load(Ordering::Relaxed);
+        let next = (head + 1) % N;
+        if next == self.tail.load(Ordering::Acquire) {
+            return false;
+        }
+        unsafe {
+            (*self.buf.get())[head].write(value);
+        }
+        self.head.store(next, Ordering::Release);
+        true
+    }
+
+    /// 移除队头元素。只能由消费者调用
+    pub fn pop(&self) -> Option<T> {
+        let tail = self.tail.load(Ordering::Relaxed);
+        if tail == self.head.load(Ordering::Acquire) {
+            return None;
+        }
+        let value = unsafe { (*self.buf.get())[tail].assume_init() };
+        self.tail.store((tail + 1) % N, Ordering::Release);
+        Some(value)
+    }
+
+    /// 清空缓冲区。只能由消费者调用
+    pub fn clear(&self) {
+        self.tail
+            .store(self.head.load(Ordering::Acquire), Ordering::Release);
+    }
+}
+
+impl<T: Copy, const N: usize> Default for SpscRingBuf<T, N> {
+    fn default() -> Self {
+        Self::new()
+    }
+}